@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+
+/// Pool of SQLite connections to one database file
+pub type DbPool = Pool<SqliteConnectionManager>;
+
+/// A connection checked out of a `DbPool` (returned to the pool on drop)
+pub type DbConnection = PooledConnection<SqliteConnectionManager>;
+
+/// Max connections per database file
+const POOL_SIZE: u32 = 8;
+
+/// One pool per database path, shared by every plugin context using that path
+static POOLS: Lazy<Mutex<HashMap<String, DbPool>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Get (or create) the connection pool for a database file
+///
+/// Connections are opened in WAL mode with foreign keys enabled and a busy
+/// timeout, so concurrent readers don't block the writer.
+pub fn get_pool(db_path: &str) -> Result<DbPool> {
+    let mut pools = POOLS.lock().unwrap();
+
+    if let Some(pool) = pools.get(db_path) {
+        return Ok(pool.clone());
+    }
+
+    if let Some(parent) = std::path::Path::new(db_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA foreign_keys = ON;
+             PRAGMA busy_timeout = 5000;",
+        )
+    });
+
+    let pool = Pool::builder()
+        .max_size(POOL_SIZE)
+        .connection_timeout(Duration::from_secs(10))
+        .build(manager)
+        .map_err(|e| anyhow!("Failed to open database pool for {}: {}", db_path, e))?;
+
+    log::info!("🗄️  Opened database pool: {} ({} connections max)", db_path, POOL_SIZE);
+    pools.insert(db_path.to_string(), pool.clone());
+    Ok(pool)
+}
+
+/// Connection counts of every open pool, keyed by database path
+pub fn pool_states() -> Vec<(String, r2d2::State)> {
+    let pools = POOLS.lock().unwrap();
+    let mut states: Vec<(String, r2d2::State)> = pools.iter()
+        .map(|(path, pool)| (path.clone(), pool.state()))
+        .collect();
+    states.sort_by(|a, b| a.0.cmp(&b.0));
+    states
+}
+
+/// Get the default app database path ({data_local_dir}/{app}/webarcade.db)
+pub fn default_database_path() -> String {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(env!("CARGO_PKG_NAME"))
+        .join("webarcade.db")
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Check that the database at `db_path` can be opened and queried
+pub fn ping(db_path: &str) -> Result<()> {
+    let conn = get_pool(db_path)?.get()?;
+    conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0))?;
+    Ok(())
+}
+
+/// Run `f` inside a transaction on `conn`
+/// Commits if `f` returns Ok, rolls back (and returns the error) otherwise
+pub fn run_in_transaction<T, F>(conn: &mut rusqlite::Connection, f: F) -> Result<T>
+where
+    F: FnOnce(&rusqlite::Transaction) -> Result<T>,
+{
+    let tx = conn.transaction()?;
+    match f(&tx) {
+        Ok(value) => {
+            tx.commit()?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(rollback_err) = tx.rollback() {
+                log::error!("Failed to roll back transaction: {}", rollback_err);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// A versioned schema change for a plugin's tables
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version number, applied in ascending order (must be unique per plugin)
+    pub version: i64,
+    /// SQL that applies the change
+    pub up: &'static str,
+    /// SQL that reverts the change
+    pub down: &'static str,
+}
+
+/// Stable checksum of a migration's `up` SQL (FNV-1a, so it doesn't change between builds)
+fn migration_checksum(sql: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in sql.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn ensure_migrations_table(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _migrations (
+            plugin_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at INTEGER NOT NULL,
+            PRIMARY KEY (plugin_id, version)
+        )",
+    )?;
+    Ok(())
+}
+
+/// Applied versions and checksums for a plugin
+fn applied_migrations(conn: &rusqlite::Connection, plugin_id: &str) -> Result<HashMap<i64, String>> {
+    let mut stmt = conn.prepare("SELECT version, checksum FROM _migrations WHERE plugin_id = ?1")?;
+    let rows = stmt.query_map([plugin_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+    let mut applied = HashMap::new();
+    for row in rows {
+        let (version, checksum) = row?;
+        applied.insert(version, checksum);
+    }
+    Ok(applied)
+}
+
+/// Apply a plugin's pending migrations in version order
+///
+/// Each migration runs in its own transaction and is recorded in `_migrations`.
+/// Fails without applying anything if an already-applied migration's SQL has
+/// changed since it ran. Returns the versions that were applied.
+pub fn migrate(conn: &mut rusqlite::Connection, plugin_id: &str, migrations: &[Migration]) -> Result<Vec<i64>> {
+    ensure_migrations_table(conn)?;
+    let applied = applied_migrations(conn, plugin_id)?;
+
+    let mut sorted: Vec<&Migration> = migrations.iter().collect();
+    sorted.sort_by_key(|m| m.version);
+
+    for pair in sorted.windows(2) {
+        if pair[0].version == pair[1].version {
+            return Err(anyhow!("Plugin '{}' declares migration version {} twice", plugin_id, pair[0].version));
+        }
+    }
+
+    for migration in &sorted {
+        if let Some(checksum) = applied.get(&migration.version) {
+            if *checksum != migration_checksum(migration.up) {
+                return Err(anyhow!(
+                    "Migration {} of plugin '{}' was changed after it was applied - add a new migration instead",
+                    migration.version, plugin_id
+                ));
+            }
+        }
+    }
+
+    let mut newly_applied = Vec::new();
+    for migration in sorted.into_iter().filter(|m| !applied.contains_key(&m.version)) {
+        run_in_transaction(conn, |tx| {
+            tx.execute_batch(migration.up)
+                .map_err(|e| anyhow!("Migration {} of plugin '{}' failed: {}", migration.version, plugin_id, e))?;
+            tx.execute(
+                "INSERT INTO _migrations (plugin_id, version, checksum, applied_at) VALUES (?1, ?2, ?3, strftime('%s','now'))",
+                rusqlite::params![plugin_id, migration.version, migration_checksum(migration.up)],
+            )?;
+            Ok(())
+        })?;
+        log::info!("[{}] Applied migration {}", plugin_id, migration.version);
+        newly_applied.push(migration.version);
+    }
+
+    Ok(newly_applied)
+}
+
+/// Revert a plugin's applied migrations above `target_version`, newest first
+/// Returns the versions that were reverted.
+pub fn rollback(conn: &mut rusqlite::Connection, plugin_id: &str, migrations: &[Migration], target_version: i64) -> Result<Vec<i64>> {
+    ensure_migrations_table(conn)?;
+    let applied = applied_migrations(conn, plugin_id)?;
+
+    let mut to_revert: Vec<&Migration> = migrations.iter()
+        .filter(|m| m.version > target_version && applied.contains_key(&m.version))
+        .collect();
+    to_revert.sort_by_key(|m| std::cmp::Reverse(m.version));
+
+    let mut reverted = Vec::new();
+    for migration in to_revert {
+        run_in_transaction(conn, |tx| {
+            tx.execute_batch(migration.down)
+                .map_err(|e| anyhow!("Reverting migration {} of plugin '{}' failed: {}", migration.version, plugin_id, e))?;
+            tx.execute(
+                "DELETE FROM _migrations WHERE plugin_id = ?1 AND version = ?2",
+                rusqlite::params![plugin_id, migration.version],
+            )?;
+            Ok(())
+        })?;
+        log::info!("[{}] Reverted migration {}", plugin_id, migration.version);
+        reverted.push(migration.version);
+    }
+
+    Ok(reverted)
+}
+
+// ==================== Settings ====================
+
+fn ensure_settings_table(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS plugin_settings (
+            plugin_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (plugin_id, key)
+        )",
+    )?;
+    Ok(())
+}
+
+/// Read a plugin setting, `None` if it was never set
+pub fn get_setting(conn: &rusqlite::Connection, plugin_id: &str, key: &str) -> Result<Option<String>> {
+    use rusqlite::OptionalExtension;
+
+    ensure_settings_table(conn)?;
+    let value = conn
+        .query_row(
+            "SELECT value FROM plugin_settings WHERE plugin_id = ?1 AND key = ?2",
+            [plugin_id, key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value)
+}
+
+/// Write a plugin setting, replacing any previous value
+pub fn set_setting(conn: &rusqlite::Connection, plugin_id: &str, key: &str, value: &str) -> Result<()> {
+    ensure_settings_table(conn)?;
+    conn.execute(
+        "INSERT INTO plugin_settings (plugin_id, key, value, updated_at)
+         VALUES (?1, ?2, ?3, strftime('%s','now'))
+         ON CONFLICT (plugin_id, key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        [plugin_id, key, value],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_items(conn: &rusqlite::Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_transaction_commits_on_success() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE items (name TEXT NOT NULL)").unwrap();
+
+        run_in_transaction(&mut conn, |tx| {
+            tx.execute("INSERT INTO items (name) VALUES ('a')", [])?;
+            tx.execute("INSERT INTO items (name) VALUES ('b')", [])?;
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(count_items(&conn), 2);
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE items (name TEXT NOT NULL)").unwrap();
+
+        let result: Result<()> = run_in_transaction(&mut conn, |tx| {
+            tx.execute("INSERT INTO items (name) VALUES ('a')", [])?;
+            Err(anyhow!("failed halfway"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(count_items(&conn), 0);
+    }
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            up: "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL)",
+            down: "DROP TABLE notes",
+        },
+        Migration {
+            version: 2,
+            up: "ALTER TABLE notes ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+            down: "ALTER TABLE notes DROP COLUMN pinned",
+        },
+    ];
+
+    #[test]
+    fn test_migrate_applies_pending_once_in_order() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        // Declared out of order on purpose
+        let reversed: Vec<Migration> = MIGRATIONS.iter().rev().copied().collect();
+        assert_eq!(migrate(&mut conn, "notes", &reversed).unwrap(), vec![1, 2]);
+        assert_eq!(migrate(&mut conn, "notes", MIGRATIONS).unwrap(), Vec::<i64>::new());
+
+        conn.execute("INSERT INTO notes (body, pinned) VALUES ('hi', 1)", []).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_rejects_changed_migration() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        migrate(&mut conn, "notes", &MIGRATIONS[..1]).unwrap();
+
+        let edited = [Migration {
+            version: 1,
+            up: "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)",
+            down: "DROP TABLE notes",
+        }];
+        assert!(migrate(&mut conn, "notes", &edited).is_err());
+    }
+
+    #[test]
+    fn test_migration_versions_are_per_plugin() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        migrate(&mut conn, "notes", &MIGRATIONS[..1]).unwrap();
+
+        let other = [Migration { version: 1, up: "CREATE TABLE other (id INTEGER)", down: "DROP TABLE other" }];
+        assert_eq!(migrate(&mut conn, "other", &other).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_rollback_reverts_newest_first() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        migrate(&mut conn, "notes", MIGRATIONS).unwrap();
+
+        assert_eq!(rollback(&mut conn, "notes", MIGRATIONS, 0).unwrap(), vec![2, 1]);
+        assert!(conn.execute("INSERT INTO notes (body) VALUES ('gone')", []).is_err());
+        assert_eq!(migrate(&mut conn, "notes", MIGRATIONS).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_settings_are_namespaced_per_plugin() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert_eq!(get_setting(&conn, "tts", "voice").unwrap(), None);
+
+        set_setting(&conn, "tts", "voice", "Brian").unwrap();
+        set_setting(&conn, "confessions", "voice", "off").unwrap();
+        set_setting(&conn, "tts", "voice", "Amy").unwrap();
+
+        assert_eq!(get_setting(&conn, "tts", "voice").unwrap().as_deref(), Some("Amy"));
+        assert_eq!(get_setting(&conn, "confessions", "voice").unwrap().as_deref(), Some("off"));
+    }
+
+    /// Benchmark: opening a connection per call vs checking one out of the pool
+    /// Run with `cargo test --release pool_vs_open -- --ignored --nocapture`
+    /// Only reports timings; it never fails on them.
+    #[test]
+    #[ignore]
+    fn bench_pool_vs_open() {
+        const CALLS: u32 = 2000;
+        let db_path = std::env::temp_dir().join(format!("webarcade_bench_pool_{}.db", std::process::id()));
+        let db_path = db_path.to_string_lossy().to_string();
+        let query = |conn: &rusqlite::Connection| -> i64 { conn.query_row("SELECT 1", [], |row| row.get(0)).unwrap() };
+
+        let pool = get_pool(&db_path).unwrap();
+        query(&pool.get().unwrap());
+
+        let started = std::time::Instant::now();
+        for _ in 0..CALLS {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;").unwrap();
+            query(&conn);
+        }
+        let opened = started.elapsed();
+
+        let started = std::time::Instant::now();
+        for _ in 0..CALLS {
+            query(&pool.get().unwrap());
+        }
+        let pooled = started.elapsed();
+
+        println!(
+            "{} calls: Connection::open {:?} ({:?}/call), pool.get {:?} ({:?}/call)",
+            CALLS, opened, opened / CALLS, pooled, pooled / CALLS
+        );
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
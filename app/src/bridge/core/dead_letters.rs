@@ -0,0 +1,138 @@
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use crate::bridge::core::events::Event;
+
+/// An event a plugin's handler gave up on, kept in `_dead_letters` for replay
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    /// Plugin whose handler failed
+    pub plugin_id: String,
+    /// Topic (or pattern) the handler subscribed to
+    pub topic: String,
+    pub event: Event,
+    /// Error from the last attempt
+    pub error: String,
+    /// Attempts made so far, across replays
+    pub attempts: u32,
+    pub failed_at: DateTime<Utc>,
+}
+
+fn ensure_dead_letters_table(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _dead_letters (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            plugin_id TEXT NOT NULL,
+            topic TEXT NOT NULL,
+            event TEXT NOT NULL,
+            error TEXT NOT NULL,
+            attempts INTEGER NOT NULL,
+            failed_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_dead_letters_plugin_topic ON _dead_letters (plugin_id, topic);",
+    )?;
+    Ok(())
+}
+
+/// Record an event that `plugin_id`'s handler for `topic` failed on
+pub fn record_dead_letter(conn: &rusqlite::Connection, plugin_id: &str, topic: &str, event: &Event, error: &str, attempts: u32) -> Result<i64> {
+    ensure_dead_letters_table(conn)?;
+    conn.execute(
+        "INSERT INTO _dead_letters (plugin_id, topic, event, error, attempts, failed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![plugin_id, topic, serde_json::to_string(event)?, error, attempts, Utc::now().timestamp()],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// A plugin's dead letters, oldest first, optionally for one topic
+pub fn list_dead_letters(conn: &rusqlite::Connection, plugin_id: &str, topic: Option<&str>) -> Result<Vec<DeadLetter>> {
+    ensure_dead_letters_table(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, plugin_id, topic, event, error, attempts, failed_at FROM _dead_letters
+         WHERE plugin_id = ?1 AND (?2 IS NULL OR topic = ?2)
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![plugin_id, topic], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, u32>(5)?,
+            row.get::<_, i64>(6)?,
+        ))
+    })?;
+
+    let mut letters = Vec::new();
+    for row in rows {
+        let (id, plugin_id, topic, event, error, attempts, failed_at) = row?;
+        letters.push(DeadLetter {
+            id,
+            plugin_id,
+            topic,
+            event: serde_json::from_str(&event)?,
+            error,
+            attempts,
+            failed_at: Utc.timestamp_opt(failed_at, 0).single().unwrap_or_default(),
+        });
+    }
+    Ok(letters)
+}
+
+/// Record another failed (replay) attempt on a dead letter
+pub fn update_dead_letter(conn: &rusqlite::Connection, id: i64, error: &str, attempts: u32) -> Result<()> {
+    ensure_dead_letters_table(conn)?;
+    conn.execute(
+        "UPDATE _dead_letters SET error = ?2, attempts = attempts + ?3, failed_at = ?4 WHERE id = ?1",
+        rusqlite::params![id, error, attempts, Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+/// Remove a dead letter (after a successful replay, or to discard it)
+pub fn delete_dead_letter(conn: &rusqlite::Connection, plugin_id: &str, id: i64) -> Result<bool> {
+    ensure_dead_letters_table(conn)?;
+    Ok(conn.execute("DELETE FROM _dead_letters WHERE id = ?1 AND plugin_id = ?2", rusqlite::params![id, plugin_id])? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(n: i64) -> Event {
+        Event {
+            source_plugin: "packs".to_string(),
+            event_type: "packs.purchase_request".to_string(),
+            timestamp: n,
+            payload: serde_json::json!({ "pack_id": n }),
+            correlation_id: None,
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn test_record_list_update_delete() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let first = record_dead_letter(&conn, "currency", "packs.*", &event(1), "database is locked", 3).unwrap();
+        record_dead_letter(&conn, "currency", "auction.bid_placed", &event(2), "boom", 1).unwrap();
+        record_dead_letter(&conn, "inventory", "packs.*", &event(3), "boom", 1).unwrap();
+
+        let letters = list_dead_letters(&conn, "currency", Some("packs.*")).unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].event.payload["pack_id"], 1);
+        assert_eq!(letters[0].attempts, 3);
+        assert_eq!(list_dead_letters(&conn, "currency", None).unwrap().len(), 2);
+
+        update_dead_letter(&conn, first, "still locked", 2).unwrap();
+        let letter = &list_dead_letters(&conn, "currency", Some("packs.*")).unwrap()[0];
+        assert_eq!(letter.attempts, 5);
+        assert_eq!(letter.error, "still locked");
+
+        // Plugins can only delete their own
+        assert!(!delete_dead_letter(&conn, "inventory", first).unwrap());
+        assert!(delete_dead_letter(&conn, "currency", first).unwrap());
+        assert!(list_dead_letters(&conn, "currency", Some("packs.*")).unwrap().is_empty());
+    }
+}
@@ -36,6 +36,9 @@ pub struct PluginConfig {
     /// Other plugin IDs this plugin depends on (will be loaded first)
     #[serde(default)]
     pub dependencies: Vec<String>,
+    /// Plugin-specific settings, read with `PluginContext::config`
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub config: serde_json::Value,
 }
 
 fn default_has_frontend() -> bool { true }
@@ -54,8 +57,46 @@ pub struct WebArcadeConfig {
     pub width: u32,
     #[serde(default = "default_height")]
     pub height: u32,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_plugins")]
     pub plugins: HashMap<String, PluginConfig>,
+    /// Refuse to install plugin zips without a valid signature
+    #[serde(default)]
+    pub require_signed_plugins: bool,
+    /// Base64 Ed25519 public keys whose plugin signatures are trusted
+    #[serde(default)]
+    pub trusted_plugin_keys: Vec<String>,
+}
+
+/// Read the `plugins` map, refusing a plugin id that appears twice
+///
+/// serde_json would otherwise keep whichever entry comes last, silently
+/// dropping the other plugin.
+fn deserialize_plugins<'de, D>(deserializer: D) -> std::result::Result<HashMap<String, PluginConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct PluginsVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for PluginsVisitor {
+        type Value = HashMap<String, PluginConfig>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a map of plugin ids to plugin entries")
+        }
+
+        fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+            let mut plugins = HashMap::new();
+            while let Some((plugin_id, plugin_config)) = map.next_entry::<String, PluginConfig>()? {
+                if plugins.contains_key(&plugin_id) {
+                    return Err(serde::de::Error::custom(format!("plugin '{}' is listed more than once", plugin_id)));
+                }
+                plugins.insert(plugin_id, plugin_config);
+            }
+            Ok(plugins)
+        }
+    }
+
+    deserializer.deserialize_map(PluginsVisitor)
 }
 
 fn default_width() -> u32 { 1280 }
@@ -79,6 +120,89 @@ impl WebArcadeConfig {
         fs::write(config_path, content)?;
         Ok(())
     }
+
+    /// Enable or disable a plugin, keeping dependencies consistent
+    ///
+    /// Refuses to disable a plugin that an enabled plugin depends on, and to
+    /// enable one whose dependencies are disabled or missing.
+    pub fn set_plugin_enabled(&mut self, plugin_id: &str, enabled: bool) -> Result<()> {
+        let plugin = self.plugins.get(plugin_id)
+            .ok_or_else(|| anyhow!("Plugin '{}' is not in the config", plugin_id))?;
+
+        if enabled {
+            let missing: Vec<&str> = plugin.dependencies.iter()
+                .filter(|dep| !self.plugins.get(*dep).map(|p| p.enabled).unwrap_or(false))
+                .map(|dep| dep.as_str())
+                .collect();
+            if !missing.is_empty() {
+                return Err(anyhow!("Plugin '{}' needs these plugins enabled first: {}", plugin_id, missing.join(", ")));
+            }
+        } else {
+            let mut dependents: Vec<&str> = self.plugins.iter()
+                .filter(|(id, p)| p.enabled && id.as_str() != plugin_id && p.dependencies.iter().any(|dep| dep == plugin_id))
+                .map(|(id, _)| id.as_str())
+                .collect();
+            dependents.sort();
+            if !dependents.is_empty() {
+                return Err(anyhow!("Plugin '{}' is required by: {}", plugin_id, dependents.join(", ")));
+            }
+        }
+
+        if let Some(plugin) = self.plugins.get_mut(plugin_id) {
+            plugin.enabled = enabled;
+        }
+        Ok(())
+    }
+}
+
+/// Edit webarcade.config.json as raw JSON and write it back atomically
+///
+/// Editing the JSON rather than `WebArcadeConfig` keeps key order and fields
+/// this version doesn't know about; writing a temp file and renaming it over
+/// the config means a crash mid-write can't leave it truncated. A missing
+/// config is created.
+pub fn edit_config_file<T>(config_path: &Path, edit: impl FnOnce(&mut serde_json::Value) -> Result<T>) -> Result<T> {
+    let mut config: serde_json::Value = if config_path.exists() {
+        let content = fs::read_to_string(config_path)
+            .map_err(|e| anyhow!("Failed to read config file: {}", e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse config file: {}", e))?
+    } else {
+        serde_json::json!({ "name": "WebArcade", "version": "0.1.0", "plugins": {} })
+    };
+
+    let result = edit(&mut config)?;
+
+    let temp_path = config_path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&temp_path, serde_json::to_string_pretty(&config)?)
+        .and_then(|_| fs::rename(&temp_path, config_path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            anyhow!("Failed to write config file: {}", e)
+        })?;
+    Ok(result)
+}
+
+/// The `plugins` object of a raw config, created if missing
+pub fn config_plugins_mut(config: &mut serde_json::Value) -> Result<&mut serde_json::Map<String, serde_json::Value>> {
+    config.as_object_mut()
+        .ok_or_else(|| anyhow!("Config file is not a JSON object"))?
+        .entry("plugins")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("\"plugins\" in the config file is not an object"))
+}
+
+/// Platform file name of a plugin's DLL (`<id>.dll`, `lib<id>.so`, `lib<id>.dylib`)
+pub fn dll_file_name(plugin_id: &str) -> String {
+    #[cfg(target_os = "windows")]
+    let dll_name = format!("{}.dll", plugin_id);
+    #[cfg(target_os = "linux")]
+    let dll_name = format!("lib{}.so", plugin_id);
+    #[cfg(target_os = "macos")]
+    let dll_name = format!("lib{}.dylib", plugin_id);
+
+    dll_name
 }
 
 pub struct DynamicPluginLoader {
@@ -86,15 +210,18 @@ pub struct DynamicPluginLoader {
     config_path: PathBuf,
 }
 
+/// webarcade.config.json for a plugins directory: the repo root, parent of app/
+pub fn default_config_path(plugins_dir: &Path) -> PathBuf {
+    plugins_dir
+        .parent() // app/
+        .and_then(|p| p.parent()) // repo root
+        .map(|p| p.join("webarcade.config.json"))
+        .unwrap_or_else(|| plugins_dir.join("../webarcade.config.json"))
+}
+
 impl DynamicPluginLoader {
     pub fn new(plugins_dir: PathBuf) -> Self {
-        // Config is in the repo root (parent of app/)
-        let config_path = plugins_dir
-            .parent() // app/
-            .and_then(|p| p.parent()) // repo root
-            .map(|p| p.join("webarcade.config.json"))
-            .unwrap_or_else(|| plugins_dir.join("../webarcade.config.json"));
-
+        let config_path = default_config_path(&plugins_dir);
         Self { plugins_dir, config_path }
     }
 
@@ -130,12 +257,22 @@ impl DynamicPluginLoader {
         for plugin in embedded::EMBEDDED_PLUGINS {
             log::info!("📦 Loading embedded plugin: {}", plugin.id);
 
+            let loaded_ids = loaded_plugin_ids(&plugins);
+            if let Err(e) = check_plugin_id(plugin.id, &loaded_ids) {
+                log::error!("❌ Skipping embedded plugin: {}", e);
+                plugins.push(PluginInfo::failed(plugin.id, e.to_string()));
+                continue;
+            }
+
             if plugin.is_dll {
                 // For DLLs, we need to write to a temp file and load it
                 // (libloading requires a file path)
                 match self.load_embedded_dll(plugin.id, plugin.data) {
                     Ok(plugin_info) => plugins.push(plugin_info),
-                    Err(e) => log::warn!("⚠️  Failed to load embedded plugin {}: {}", plugin.id, e),
+                    Err(e) => {
+                        log::error!("❌ Embedded plugin {} failed to load: {}", plugin.id, e);
+                        plugins.push(PluginInfo::failed(plugin.id, e.to_string()));
+                    }
                 }
             } else {
                 // JS plugin - store the data directly
@@ -155,12 +292,15 @@ impl DynamicPluginLoader {
                     routes: vec![],
                     frontend_path: None,
                     embedded_js: Some(plugin.id.to_string()),
+                    status: PluginStatus::Loaded,
+                    error: None,
+                    artifact_path: None,
                 });
                 log::info!("✅ Loaded embedded JS plugin: {}", plugin.id);
             }
         }
 
-        log::info!("📦 Successfully loaded {} embedded plugins", plugins.len());
+        log::info!("📦 Successfully loaded {} embedded plugins", loaded_plugin_ids(&plugins).len());
         Ok(plugins)
     }
 
@@ -198,12 +338,14 @@ impl DynamicPluginLoader {
         let config = WebArcadeConfig::load(&self.config_path)?;
         let mut plugins = Vec::new();
 
-        // Filter enabled plugins
+        // Filter enabled plugins; disabled ones are still listed
+        let mut disabled = Vec::new();
         let enabled_plugins: HashMap<String, PluginConfig> = config.plugins
             .into_iter()
             .filter(|(id, cfg)| {
                 if !cfg.enabled {
                     log::info!("⏭️  Skipping disabled plugin: {}", id);
+                    disabled.push(self.disabled_entry(id, cfg));
                     false
                 } else {
                     true
@@ -220,57 +362,112 @@ impl DynamicPluginLoader {
 
             log::info!("📦 Loading plugin from config: {}", plugin_id);
 
-            if plugin_config.has_backend {
-                // Load DLL plugin
-                let dll_path = self.resolve_dll_path(&plugin_id);
-
-                if dll_path.exists() {
-                    match self.load_plugin_from_dll(&dll_path, &plugin_id) {
-                        Ok(mut plugin_info) => {
-                            // Override with config values
-                            plugin_info.name = plugin_config.name.clone();
-                            plugin_info.version = plugin_config.version.clone();
-                            plugin_info.description = plugin_config.description.clone();
-                            plugin_info.author = plugin_config.author.clone();
-                            plugin_info.priority = plugin_config.priority;
-                            plugins.push(plugin_info);
-                        }
-                        Err(e) => log::warn!("⚠️  Failed to load DLL plugin {}: {}", plugin_id, e),
-                    }
-                } else {
-                    log::warn!("⚠️  DLL not found for plugin {}: {:?}", plugin_id, dll_path);
-                }
-            } else {
-                // Frontend-only JS plugin
-                let js_path = self.plugins_dir.join(&plugin_config.path);
-
-                if js_path.exists() {
-                    plugins.push(PluginInfo {
-                        id: plugin_id.clone(),
-                        name: plugin_config.name.clone(),
-                        version: plugin_config.version.clone(),
-                        description: plugin_config.description.clone(),
-                        author: plugin_config.author.clone(),
-                        dll_path: PathBuf::new(),
-                        has_backend: false,
-                        has_frontend: true,
-                        priority: plugin_config.priority,
-                        routes: vec![],
-                        frontend_path: Some(js_path),
-                        #[cfg(feature = "locked-plugins")]
-                        embedded_js: None,
-                    });
-                    log::info!("✅ Loaded frontend plugin: {}", plugin_id);
-                } else {
-                    log::warn!("⚠️  JS file not found for plugin {}: {:?}", plugin_id, js_path);
+            let loaded_ids = loaded_plugin_ids(&plugins);
+            if let Err(e) = check_plugin_id(&plugin_id, &loaded_ids) {
+                log::error!("❌ Skipping plugin: {}", e);
+                plugins.push(self.failed_entry(&plugin_id, plugin_config, e.to_string()));
+                continue;
+            }
+
+            // Failed plugins stay in the list (without routes) so /api/plugins/list can say why
+            match self.load_configured_plugin(&plugin_id, plugin_config) {
+                Ok(plugin_info) => plugins.push(plugin_info),
+                Err(e) => {
+                    log::error!("❌ Plugin {} failed to load: {}", plugin_id, e);
+                    plugins.push(self.failed_entry(&plugin_id, plugin_config, e.to_string()));
                 }
             }
         }
 
-        log::info!("📦 Successfully loaded {} plugins from config", plugins.len());
+        log::info!("📦 Successfully loaded {} plugins from config", loaded_plugin_ids(&plugins).len());
+        disabled.sort_by(|a: &PluginInfo, b| a.id.cmp(&b.id));
+        plugins.extend(disabled);
         Ok(plugins)
     }
 
+    /// Load one enabled plugin from the config, reloading its DLL
+    /// Used by /api/plugins/{id}/reload so other plugins are left untouched;
+    /// `loaded_ids` are the plugins still running, whose ids can't be reused.
+    pub fn load_plugin(&mut self, plugin_id: &str, loaded_ids: &[&str]) -> Result<PluginInfo> {
+        let config = WebArcadeConfig::load(&self.config_path)?;
+        let plugin_config = config.plugins.get(plugin_id)
+            .ok_or_else(|| anyhow!("Plugin '{}' is not in the config", plugin_id))?;
+        if !plugin_config.enabled {
+            return Err(anyhow!("Plugin '{}' is disabled", plugin_id));
+        }
+        check_plugin_id(plugin_id, loaded_ids)?;
+
+        self.load_configured_plugin(plugin_id, plugin_config)
+    }
+
+    /// Listing entry for a plugin that is disabled in the config
+    pub fn disabled_entry(&self, plugin_id: &str, plugin_config: &PluginConfig) -> PluginInfo {
+        let mut plugin_info = PluginInfo::failed(plugin_id, String::new()).with_config(plugin_config);
+        plugin_info.status = PluginStatus::Disabled;
+        plugin_info.error = None;
+        plugin_info.artifact_path = Some(self.configured_artifact_path(plugin_id, plugin_config));
+        plugin_info
+    }
+
+    /// Listing entry for a configured plugin that failed to load
+    fn failed_entry(&self, plugin_id: &str, plugin_config: &PluginConfig, error: String) -> PluginInfo {
+        let mut plugin_info = PluginInfo::failed(plugin_id, error).with_config(plugin_config);
+        plugin_info.artifact_path = Some(self.configured_artifact_path(plugin_id, plugin_config));
+        plugin_info
+    }
+
+    /// Where a configured plugin's DLL (or JS file, for frontend-only plugins) is expected
+    fn configured_artifact_path(&self, plugin_id: &str, plugin_config: &PluginConfig) -> PathBuf {
+        if plugin_config.has_backend {
+            self.configured_dll_path(plugin_id, plugin_config)
+        } else {
+            self.plugins_dir.join(&plugin_config.path)
+        }
+    }
+
+    /// Load a plugin's DLL (or locate its JS file) as described by its config entry
+    fn load_configured_plugin(&mut self, plugin_id: &str, plugin_config: &PluginConfig) -> Result<PluginInfo> {
+        if plugin_config.has_backend {
+            // Load DLL plugin
+            let dll_path = self.configured_dll_path(plugin_id, plugin_config);
+            if !dll_path.exists() {
+                return Err(anyhow!("DLL not found for plugin {}: {:?}", plugin_id, dll_path));
+            }
+
+            let plugin_info = self.load_plugin_from_dll(&dll_path, plugin_id)
+                .map_err(|e| anyhow!("Failed to load DLL plugin {}: {}", plugin_id, e))?;
+
+            // Override with config values
+            Ok(plugin_info.with_config(plugin_config))
+        } else {
+            // Frontend-only JS plugin
+            let js_path = self.plugins_dir.join(&plugin_config.path);
+            if !js_path.exists() {
+                return Err(anyhow!("JS file not found for plugin {}: {:?}", plugin_id, js_path));
+            }
+
+            log::info!("✅ Loaded frontend plugin: {}", plugin_id);
+            Ok(PluginInfo {
+                id: plugin_id.to_string(),
+                name: plugin_config.name.clone(),
+                version: plugin_config.version.clone(),
+                description: plugin_config.description.clone(),
+                author: plugin_config.author.clone(),
+                dll_path: PathBuf::new(),
+                has_backend: false,
+                has_frontend: true,
+                priority: plugin_config.priority,
+                routes: vec![],
+                frontend_path: Some(js_path.clone()),
+                #[cfg(feature = "locked-plugins")]
+                embedded_js: None,
+                status: PluginStatus::Loaded,
+                error: None,
+                artifact_path: Some(js_path),
+            })
+        }
+    }
+
     /// Resolve plugin load order using topological sort based on dependencies.
     /// Uses priority as a tiebreaker when plugins have no dependency relationship.
     fn resolve_plugin_dependencies(&self, plugins: &HashMap<String, PluginConfig>) -> Result<Vec<String>> {
@@ -353,22 +550,56 @@ impl DynamicPluginLoader {
 
     /// Resolve DLL path for a plugin
     fn resolve_dll_path(&self, plugin_id: &str) -> PathBuf {
-        #[cfg(target_os = "windows")]
-        let dll_name = format!("{}.dll", plugin_id);
-        #[cfg(target_os = "linux")]
-        let dll_name = format!("lib{}.so", plugin_id);
-        #[cfg(target_os = "macos")]
-        let dll_name = format!("lib{}.dylib", plugin_id);
+        self.plugins_dir.join(dll_file_name(plugin_id))
+    }
 
-        self.plugins_dir.join(&dll_name)
+    /// The DLL named by the config's `path` (installed plugins live in
+    /// `<id>/`), falling back to the top-level `resolve_dll_path` location
+    fn configured_dll_path(&self, plugin_id: &str, plugin_config: &PluginConfig) -> PathBuf {
+        let configured = self.plugins_dir.join(&plugin_config.path);
+        let is_dll = configured.file_name().and_then(|name| name.to_str()) == Some(dll_file_name(plugin_id).as_str());
+        if is_dll && configured.is_file() {
+            configured
+        } else {
+            self.resolve_dll_path(plugin_id)
+        }
     }
 
     fn load_plugin_from_dll(&mut self, dll_path: &Path, plugin_id: &str) -> Result<PluginInfo> {
         log::info!("📦 Loading plugin DLL: {} from {:?}", plugin_id, dll_path);
 
-        // Load the library
-        let lib = unsafe { Library::new(dll_path)? };
-        let lib_arc = Arc::new(lib);
+        // Reuse the loaded library if the DLL hasn't changed since it was loaded,
+        // otherwise load the new build. Requests already in flight hold their own
+        // Arc to the old library, so it is only closed once they finish.
+        let mtime = fs::metadata(dll_path).and_then(|m| m.modified()).ok();
+        let existing = crate::bridge::core::plugin_exports::get_plugin_library(plugin_id);
+        let loaded_mtime = crate::bridge::core::plugin_exports::get_library_mtime(plugin_id);
+
+        let lib_arc = match (existing, mtime, loaded_mtime) {
+            (Some(lib), Some(current), Some(loaded)) if current == loaded => {
+                log::info!("♻️  Plugin DLL unchanged, keeping loaded library: {}", plugin_id);
+                lib
+            }
+            _ => {
+                let load_path = self.shadow_copy_dll(dll_path, plugin_id, mtime);
+                let lib = unsafe { Library::new(&load_path)? };
+                Arc::new(lib)
+            }
+        };
+
+        // Refuse DLLs built against an incompatible API before calling anything else in them
+        let api_version = self.get_api_version_from_dll(&lib_arc);
+        if let Err(e) = check_api_version(api_version.as_deref(), crate::bridge::core::plugin_exports::PLUGIN_API_MAJOR) {
+            log::error!("❌ Refusing plugin {}: {}", plugin_id, e);
+            return Err(anyhow!("Plugin '{}' is incompatible: {}", plugin_id, e));
+        }
+        if api_version.is_none() {
+            log::warn!("⚠️  Plugin {} does not export plugin_api_version; rebuild it to enable compatibility checks", plugin_id);
+        }
+
+        if let Some(mtime) = mtime {
+            crate::bridge::core::plugin_exports::record_library_mtime(plugin_id, mtime);
+        }
 
         // Get manifest from the DLL
         let manifest = self.get_manifest_from_dll(&lib_arc)?;
@@ -380,6 +611,12 @@ impl DynamicPluginLoader {
             .cloned()
             .unwrap_or_default();
 
+        // Every declared handler must exist, otherwise each request to it would 500
+        let missing = missing_handler_symbols(&lib_arc, &routes);
+        if !missing.is_empty() {
+            return Err(anyhow!("DLL does not export declared handler(s): {}", missing.join(", ")));
+        }
+
         // Check if plugin has frontend
         let has_frontend = self.check_has_frontend(&lib_arc);
 
@@ -406,9 +643,65 @@ impl DynamicPluginLoader {
             frontend_path: None,
             #[cfg(feature = "locked-plugins")]
             embedded_js: None,
+            status: PluginStatus::Loaded,
+            error: None,
+            artifact_path: Some(dll_path.to_path_buf()),
         })
     }
 
+    /// Copy a plugin DLL to a versioned file in the temp directory and return the copy's path.
+    ///
+    /// Loading from a copy leaves the original free to be overwritten by the next build
+    /// (Windows locks loaded DLLs), and the unique name makes the OS load a fresh module
+    /// instead of handing back the already-loaded one. Falls back to the original path
+    /// if the copy can't be made.
+    fn shadow_copy_dll(&self, dll_path: &Path, plugin_id: &str, mtime: Option<std::time::SystemTime>) -> PathBuf {
+        let shadow_dir = std::env::temp_dir().join("webarcade_plugins");
+        if let Err(e) = fs::create_dir_all(&shadow_dir) {
+            log::warn!("⚠️  Could not create shadow dir {:?}, loading DLL in place: {}", shadow_dir, e);
+            return dll_path.to_path_buf();
+        }
+
+        let stamp = mtime
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let extension = dll_path.extension().and_then(|e| e.to_str()).unwrap_or("dll");
+        let prefix = format!("{}-", plugin_id);
+        let shadow_path = shadow_dir.join(format!("{}{}.{}", prefix, stamp, extension));
+
+        // "{plugin_id}-{digits}.{ext}" - the digit check keeps "foo" from matching "foo-bar" copies
+        let is_copy_of_plugin = |name: &str| {
+            name.strip_prefix(&prefix)
+                .and_then(|rest| rest.split('.').next())
+                .map(|stamp| !stamp.is_empty() && stamp.chars().all(|c| c.is_ascii_digit()))
+                .unwrap_or(false)
+        };
+
+        // Best-effort cleanup of older copies (still-loaded ones will fail to delete on Windows)
+        if let Ok(entries) = fs::read_dir(&shadow_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                if path != shadow_path && is_copy_of_plugin(name) {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+
+        if shadow_path.exists() {
+            return shadow_path;
+        }
+
+        match fs::copy(dll_path, &shadow_path) {
+            Ok(_) => shadow_path,
+            Err(e) => {
+                log::warn!("⚠️  Could not shadow-copy {:?}, loading DLL in place: {}", dll_path, e);
+                dll_path.to_path_buf()
+            }
+        }
+    }
+
     fn get_manifest_from_dll(&self, lib: &Arc<Library>) -> Result<serde_json::Value> {
         type GetManifestFn = unsafe extern "C" fn() -> *const u8;
         type GetManifestLenFn = unsafe extern "C" fn() -> usize;
@@ -432,6 +725,20 @@ impl DynamicPluginLoader {
         }
     }
 
+    /// Read the optional `plugin_api_version` export (a NUL-terminated semver string)
+    fn get_api_version_from_dll(&self, lib: &Arc<Library>) -> Option<String> {
+        type ApiVersionFn = unsafe extern "C" fn() -> *const std::os::raw::c_char;
+
+        unsafe {
+            let api_version = lib.get::<ApiVersionFn>(b"plugin_api_version").ok()?;
+            let ptr = api_version();
+            if ptr.is_null() {
+                return None;
+            }
+            Some(std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned())
+        }
+    }
+
     fn check_has_frontend(&self, lib: &Arc<Library>) -> bool {
         type HasFrontendFn = unsafe extern "C" fn() -> bool;
 
@@ -494,4 +801,279 @@ pub struct PluginInfo {
     /// Key for embedded JS content (locked-plugins mode)
     #[cfg(feature = "locked-plugins")]
     pub embedded_js: Option<String>,
+    pub status: PluginStatus,
+    /// Why the plugin failed to load
+    pub error: Option<String>,
+    /// DLL or JS file the plugin was (or would be) loaded from; `None` when embedded
+    pub artifact_path: Option<PathBuf>,
+}
+
+/// Whether a discovered plugin is serving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginStatus {
+    Loaded,
+    /// Found but not loadable (missing/incompatible DLL, missing handlers); no routes registered
+    Failed,
+    /// Turned off in webarcade.config.json
+    Disabled,
+}
+
+/// One entry of `/api/plugins/list`
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginListing {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub author: String,
+    pub priority: i32,
+    pub status: PluginStatus,
+    pub error: Option<String>,
+    pub artifact_path: Option<String>,
+    pub route_count: usize,
+    pub routes: Vec<serde_json::Value>,
+    pub has_plugin_js: bool,
+    pub has_dll: bool,
+}
+
+impl PluginInfo {
+    /// Entry for a plugin that could not be loaded
+    pub fn failed(plugin_id: &str, error: String) -> Self {
+        Self {
+            id: plugin_id.to_string(),
+            name: plugin_id.to_string(),
+            version: String::new(),
+            description: String::new(),
+            author: String::new(),
+            dll_path: PathBuf::new(),
+            has_backend: false,
+            has_frontend: false,
+            priority: 100,
+            routes: vec![],
+            frontend_path: None,
+            #[cfg(feature = "locked-plugins")]
+            embedded_js: None,
+            status: PluginStatus::Failed,
+            error: Some(error),
+            artifact_path: None,
+        }
+    }
+
+    /// Take name, version, description, author and priority from the config entry
+    fn with_config(mut self, plugin_config: &PluginConfig) -> Self {
+        self.name = plugin_config.name.clone();
+        self.version = plugin_config.version.clone();
+        self.description = plugin_config.description.clone();
+        self.author = plugin_config.author.clone();
+        self.priority = plugin_config.priority;
+        self
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.status == PluginStatus::Loaded
+    }
+
+    /// This plugin as listed by `/api/plugins/list`
+    pub fn listing(&self) -> PluginListing {
+        PluginListing {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            author: self.author.clone(),
+            priority: self.priority,
+            status: self.status,
+            error: self.error.clone(),
+            artifact_path: self.artifact_path.as_ref().map(|p| p.to_string_lossy().into_owned()),
+            route_count: self.routes.len(),
+            routes: self.routes.clone(),
+            has_plugin_js: self.has_frontend,
+            has_dll: self.has_backend,
+        }
+    }
+}
+
+/// Ids of the successfully loaded plugins in `plugins`
+pub fn loaded_plugin_ids(plugins: &[PluginInfo]) -> Vec<&str> {
+    plugins.iter().filter(|p| p.is_loaded()).map(|p| p.id.as_str()).collect()
+}
+
+/// Handler names declared in `routes` that `lib` doesn't export, sorted and deduplicated
+fn missing_handler_symbols(lib: &Library, routes: &[serde_json::Value]) -> Vec<String> {
+    let handlers: std::collections::BTreeSet<&str> = routes.iter()
+        .filter_map(|route| route.get("handler").and_then(|h| h.as_str()))
+        .collect();
+
+    handlers.into_iter()
+        .filter(|handler| unsafe { lib.get::<*const ()>(handler.as_bytes()) }.is_err())
+        .map(str::to_string)
+        .collect()
+}
+
+/// First path segments the bridge handles itself; a plugin with one of these
+/// ids would never receive its routes
+pub const RESERVED_PLUGIN_IDS: &[&str] = &["api", "assets", "health", "metrics"];
+
+/// Reject plugin ids that are reserved or already claimed by a loaded plugin
+fn check_plugin_id(plugin_id: &str, loaded_ids: &[&str]) -> Result<()> {
+    if RESERVED_PLUGIN_IDS.contains(&plugin_id) {
+        return Err(anyhow!("'{}' is a reserved plugin id", plugin_id));
+    }
+    if loaded_ids.contains(&plugin_id) {
+        return Err(anyhow!("another plugin already uses the id '{}'", plugin_id));
+    }
+    Ok(())
+}
+
+/// Check a plugin's reported API version against the host's major version
+/// Plugins that predate the `plugin_api_version` export (`None`) are allowed
+fn check_api_version(plugin_version: Option<&str>, host_major: u64) -> Result<()> {
+    let Some(version) = plugin_version else { return Ok(()) };
+
+    let major = version.trim().split('.').next()
+        .and_then(|major| major.parse::<u64>().ok())
+        .ok_or_else(|| anyhow!("unreadable plugin API version '{}'", version))?;
+
+    if major != host_major {
+        return Err(anyhow!(
+            "built against plugin API {} but this host supports {}.x; rebuild the plugin",
+            version, host_major
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WebArcadeConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "test",
+            "version": "1.0.0",
+            "plugins": {
+                "currency": { "name": "Currency", "version": "1.0.0", "path": "currency" },
+                "packs": { "name": "Packs", "version": "1.0.0", "path": "packs", "dependencies": ["currency"] }
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_cannot_disable_a_dependency_of_an_enabled_plugin() {
+        let mut config = config();
+        let err = config.set_plugin_enabled("currency", false).unwrap_err();
+        assert!(err.to_string().contains("packs"));
+        assert!(config.plugins["currency"].enabled);
+
+        config.set_plugin_enabled("packs", false).unwrap();
+        config.set_plugin_enabled("currency", false).unwrap();
+        assert!(!config.plugins["currency"].enabled);
+    }
+
+    #[test]
+    fn test_cannot_enable_with_disabled_dependency() {
+        let mut config = config();
+        config.set_plugin_enabled("packs", false).unwrap();
+        config.set_plugin_enabled("currency", false).unwrap();
+
+        assert!(config.set_plugin_enabled("packs", true).is_err());
+        config.set_plugin_enabled("currency", true).unwrap();
+        config.set_plugin_enabled("packs", true).unwrap();
+        assert!(config.set_plugin_enabled("missing", true).is_err());
+    }
+
+    #[test]
+    fn test_api_version_must_match_host_major() {
+        assert!(check_api_version(Some("1.4.2"), 1).is_ok());
+        assert!(check_api_version(None, 1).is_ok());
+
+        let err = check_api_version(Some("2.0.0"), 1).unwrap_err();
+        assert!(err.to_string().contains("rebuild the plugin"));
+        assert!(check_api_version(Some("garbage"), 1).is_err());
+    }
+
+    #[test]
+    fn test_reserved_and_duplicate_plugin_ids_are_rejected() {
+        assert!(check_plugin_id("currency", &["packs"]).is_ok());
+        assert!(check_plugin_id("api", &[]).unwrap_err().to_string().contains("reserved"));
+        assert!(check_plugin_id("packs", &["packs"]).unwrap_err().to_string().contains("already uses"));
+    }
+
+    #[test]
+    fn test_duplicate_plugin_ids_in_config_are_rejected() {
+        let json = r#"{"name": "test", "version": "1.0.0", "plugins": {
+            "themes": {"name": "Themes", "version": "1.0.0", "path": "themes.js"},
+            "themes": {"name": "Other Themes", "version": "2.0.0", "path": "other.js"}
+        }}"#;
+        let err = serde_json::from_str::<WebArcadeConfig>(json).unwrap_err();
+        assert!(err.to_string().contains("'themes' is listed more than once"));
+    }
+
+    #[cfg(not(feature = "locked-plugins"))]
+    #[test]
+    fn test_load_plugin_rejects_id_of_running_plugin() {
+        let dir = std::env::temp_dir().join(format!("webarcade_loader_reload_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("themes.js"), "export default {}").unwrap();
+        let config_path = dir.join("webarcade.config.json");
+        fs::write(&config_path, serde_json::json!({
+            "name": "test",
+            "version": "1.0.0",
+            "plugins": { "themes": { "name": "Themes", "version": "1.0.0", "path": "themes.js" } }
+        }).to_string()).unwrap();
+
+        let mut loader = DynamicPluginLoader::new(dir.clone()).with_config_path(config_path);
+        let err = loader.load_plugin("themes", &["themes"]).unwrap_err();
+        assert!(err.to_string().contains("already uses"));
+        assert!(loader.load_plugin("themes", &["packs"]).unwrap().is_loaded());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(not(feature = "locked-plugins"))]
+    #[test]
+    fn test_unloadable_plugins_are_listed_as_failed() {
+        let dir = std::env::temp_dir().join(format!("webarcade_loader_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("themes.js"), "export default {}").unwrap();
+        let config_path = dir.join("webarcade.config.json");
+        fs::write(&config_path, serde_json::json!({
+            "name": "test",
+            "version": "1.0.0",
+            "plugins": {
+                "themes": { "name": "Themes", "version": "1.0.0", "path": "themes.js" },
+                "withings": { "name": "Withings", "version": "0.2.0", "path": "withings", "hasBackend": true },
+                "hue": { "name": "Hue", "version": "0.1.0", "path": "hue.js", "enabled": false }
+            }
+        }).to_string()).unwrap();
+
+        let mut loader = DynamicPluginLoader::new(dir.clone()).with_config_path(config_path);
+        let mut plugins = loader.load_all_plugins().unwrap();
+        plugins.sort_by(|a, b| a.id.cmp(&b.id));
+
+        assert_eq!(plugins[0].id, "hue");
+        assert_eq!(plugins[0].status, PluginStatus::Disabled);
+        assert_eq!(plugins[0].error, None);
+        assert_eq!(plugins[0].artifact_path, Some(dir.join("hue.js")));
+
+        assert_eq!(plugins[1].id, "themes");
+        assert!(plugins[1].is_loaded());
+        assert_eq!(plugins[1].artifact_path, Some(dir.join("themes.js")));
+
+        assert_eq!(plugins[2].id, "withings");
+        assert_eq!(plugins[2].status, PluginStatus::Failed);
+        assert_eq!(plugins[2].version, "0.2.0");
+        assert!(plugins[2].error.as_deref().unwrap().contains("DLL not found"));
+        assert!(plugins[2].routes.is_empty());
+
+        let listing = serde_json::to_value(plugins[2].listing()).unwrap();
+        assert_eq!(listing["status"], "failed");
+        assert_eq!(listing["route_count"], 0);
+        assert!(listing["artifact_path"].as_str().unwrap().contains("withings"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -1,6 +1,12 @@
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -18,6 +24,41 @@ pub struct Event {
 
     /// Event payload (plugins deserialize this themselves)
     pub payload: Value,
+
+    /// Correlation id linking a request event to its reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+
+    /// Topic the requester is waiting on for a reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+}
+
+/// Counter for request correlation ids
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Default number of recent events kept for `recent()` and replay
+pub const DEFAULT_HISTORY_CAPACITY: usize = 500;
+
+/// Handler registered with `EventBus::subscribe_handler`
+pub type EventHandler = Arc<dyn Fn(Event) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Called with (event, error, attempts) once a handler has given up on an event
+pub type FailureSink = Arc<dyn Fn(&Event, &str, u32) + Send + Sync>;
+
+/// How often a handler is retried before its event is handed to the failure sink
+#[derive(Debug, Clone, Copy)]
+pub struct HandlerRetry {
+    /// Total attempts, including the first (1 = no retry)
+    pub max_attempts: u32,
+    /// Delay before the second attempt, doubled for each one after
+    pub initial_backoff: Duration,
+}
+
+impl Default for HandlerRetry {
+    fn default() -> Self {
+        Self { max_attempts: 3, initial_backoff: Duration::from_millis(200) }
+    }
 }
 
 /// Event bus - completely generic, knows nothing about specific events
@@ -26,46 +67,177 @@ pub struct EventBus {
     sender: broadcast::Sender<Event>,
 
     /// Typed channels for specific event types (optional, for performance)
+    ///
+    /// std locks: publish is sync and only holds them for a lookup, so it
+    /// waits briefly for a concurrent subscribe instead of skipping the channel
     typed_channels: Arc<RwLock<HashMap<String, broadcast::Sender<Event>>>>,
+
+    /// Channels for wildcard subscriptions (e.g., "currency.*"), keyed by pattern
+    pattern_channels: Arc<RwLock<HashMap<String, broadcast::Sender<Event>>>>,
+
+    /// Ring buffer of recently published events (oldest first)
+    history: Mutex<VecDeque<Event>>,
+
+    /// Max events kept in `history`
+    history_capacity: usize,
+
+    /// Handlers by (subscriber, topic), so failed events can be replayed to them
+    handlers: Mutex<HashMap<(String, String), (EventHandler, HandlerRetry)>>,
 }
 
 impl EventBus {
     pub fn new() -> Self {
+        Self::with_history_capacity(DEFAULT_HISTORY_CAPACITY)
+    }
+
+    /// Create an event bus that keeps the last `capacity` events (0 disables history)
+    pub fn with_history_capacity(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(1000);
         Self {
             sender,
             typed_channels: Arc::new(RwLock::new(HashMap::new())),
+            pattern_channels: Arc::new(RwLock::new(HashMap::new())),
+            history: Mutex::new(VecDeque::with_capacity(capacity)),
+            history_capacity: capacity,
+            handlers: Mutex::new(HashMap::new()),
         }
     }
 
     /// Publish event to all subscribers
     pub fn publish(&self, event: Event) {
+        // Hold the history lock while sending so replaying subscribers
+        // never miss or double-see an event
+        let mut history = self.history.lock().unwrap();
+        if self.history_capacity > 0 {
+            if history.len() >= self.history_capacity {
+                history.pop_front();
+            }
+            history.push_back(event.clone());
+        }
+
         let _ = self.sender.send(event.clone());
 
         // Also send to typed channel if it exists
-        if let Ok(channels) = self.typed_channels.try_read() {
-            if let Some(typed_sender) = channels.get(&event.event_type) {
-                let _ = typed_sender.send(event);
+        if let Some(typed_sender) = self.typed_channels.read().unwrap().get(&event.event_type) {
+            let _ = typed_sender.send(event.clone());
+        }
+
+        // Wildcard subscribers are only checked when someone registered one
+        for (pattern, pattern_sender) in self.pattern_channels.read().unwrap().iter() {
+            if topic_matches(pattern, &event.event_type) {
+                let _ = pattern_sender.send(event.clone());
             }
         }
     }
 
+    /// Recent events whose type starts with `topic_prefix`, oldest first
+    ///
+    /// Returns at most `limit` of the newest matching events. An empty
+    /// prefix matches everything.
+    pub fn recent(&self, topic_prefix: &str, limit: usize) -> Vec<Event> {
+        let history = self.history.lock().unwrap();
+        let mut events: Vec<Event> = history.iter()
+            .rev()
+            .filter(|event| event.event_type.starts_with(topic_prefix))
+            .take(limit)
+            .cloned()
+            .collect();
+        events.reverse();
+        events
+    }
+
     /// Subscribe to ALL events
     pub fn subscribe(&self) -> broadcast::Receiver<Event> {
         self.sender.subscribe()
     }
 
     /// Subscribe to specific event type (e.g., "auction.bid_placed")
+    ///
+    /// `*` stands for one dot-separated segment, and a trailing `*` for
+    /// everything after it: "currency.*", "*.opened", or "*" for all events.
     pub async fn subscribe_to(&self, event_type: &str) -> broadcast::Receiver<Event> {
-        let mut channels = self.typed_channels.write().await;
+        self.channel_for(event_type).await.subscribe()
+    }
+
+    /// Sender for an exact or wildcard subscription, created on first use
+    async fn channel_for(&self, event_type: &str) -> broadcast::Sender<Event> {
+        let channels = if event_type.contains('*') {
+            &self.pattern_channels
+        } else {
+            &self.typed_channels
+        };
+        let mut channels = channels.write().unwrap();
 
-        let sender = channels.entry(event_type.to_string())
+        channels.entry(event_type.to_string())
             .or_insert_with(|| {
                 let (tx, _) = broadcast::channel(100);
                 tx
-            });
+            })
+            .clone()
+    }
+
+    /// Subscribe to an event type (or pattern) and get up to `replay` of its most recent events
+    pub async fn subscribe_to_with_replay(&self, event_type: &str, replay: usize) -> (Vec<Event>, broadcast::Receiver<Event>) {
+        let sender = self.channel_for(event_type).await;
+
+        // Subscribe and snapshot under the history lock so nothing falls in between
+        let history = self.history.lock().unwrap();
+        let receiver = sender.subscribe();
+        let mut missed: Vec<Event> = history.iter()
+            .rev()
+            .filter(|event| topic_matches(event_type, &event.event_type))
+            .take(replay)
+            .cloned()
+            .collect();
+        missed.reverse();
 
-        sender.subscribe()
+        (missed, receiver)
+    }
+
+    /// Run `handler` for every event on `topic`, retrying failures
+    ///
+    /// Events are handled one at a time in order. A handler that returns an
+    /// error (or panics, in debug builds; release builds abort) is retried
+    /// per `retry`; after the last attempt the event goes to `on_failure`
+    /// instead of being lost. Abort the returned handle to stop handling.
+    pub async fn subscribe_handler(
+        &self,
+        subscriber: &str,
+        topic: &str,
+        retry: HandlerRetry,
+        handler: EventHandler,
+        on_failure: FailureSink,
+    ) -> JoinHandle<()> {
+        let mut receiver = self.subscribe_to(topic).await;
+        self.handlers.lock().unwrap()
+            .insert((subscriber.to_string(), topic.to_string()), (handler.clone(), retry));
+
+        let subscriber = subscriber.to_string();
+        let topic = topic.to_string();
+        tokio::spawn(async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("⚠️  [{}] Handler for '{}' lagged, {} events skipped", subscriber, topic, skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                if let Err((error, attempts)) = handle_with_retry(&handler, &event, retry).await {
+                    log::error!("❌ [{}] Handler for '{}' failed after {} attempts: {}", subscriber, event.event_type, attempts, error);
+                    on_failure(&event, &error, attempts);
+                }
+            }
+        })
+    }
+
+    /// Handler registered by `subscriber` for `topic`
+    pub fn handler(&self, subscriber: &str, topic: &str) -> Option<(EventHandler, HandlerRetry)> {
+        self.handlers.lock().unwrap()
+            .get(&(subscriber.to_string(), topic.to_string()))
+            .cloned()
     }
 
     /// Helper to publish typed events (used by plugins)
@@ -75,9 +247,72 @@ impl EventBus {
             event_type: event_type.to_string(),
             timestamp: current_timestamp(),
             payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            correlation_id: None,
+            reply_to: None,
         };
         self.publish(event);
     }
+
+    /// Publish a request event and wait for its reply
+    ///
+    /// The event carries a fresh correlation id and `reply_to` topic
+    /// (`"{event_type}.reply"`). Replies for other requests on the same
+    /// topic are ignored. Fails if no reply arrives within `timeout`.
+    pub async fn request<T: Serialize>(&self, source_plugin: &str, event_type: &str, payload: &T, timeout: Duration) -> Result<Value> {
+        let correlation_id = format!(
+            "{}-{}-{}",
+            source_plugin,
+            current_timestamp(),
+            NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let reply_topic = format!("{}.reply", event_type);
+
+        // Subscribe before publishing so a fast reply can't be missed
+        let mut replies = self.subscribe_to(&reply_topic).await;
+
+        self.publish(Event {
+            source_plugin: source_plugin.to_string(),
+            event_type: event_type.to_string(),
+            timestamp: current_timestamp(),
+            payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            correlation_id: Some(correlation_id.clone()),
+            reply_to: Some(reply_topic),
+        });
+
+        let wait_for_reply = async {
+            loop {
+                match replies.recv().await {
+                    Ok(reply) if reply.correlation_id.as_deref() == Some(correlation_id.as_str()) => {
+                        return Ok(reply.payload);
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(anyhow!("Reply channel for '{}' closed", event_type));
+                    }
+                }
+            }
+        };
+
+        tokio::time::timeout(timeout, wait_for_reply)
+            .await
+            .map_err(|_| anyhow!("Request '{}' timed out after {}ms", event_type, timeout.as_millis()))?
+    }
+
+    /// Reply to a request event received from `request()`
+    pub fn reply<T: Serialize>(&self, source_plugin: &str, request: &Event, payload: &T) -> Result<()> {
+        let reply_to = request.reply_to.as_ref()
+            .ok_or_else(|| anyhow!("Event '{}' is not a request (no reply_to)", request.event_type))?;
+
+        self.publish(Event {
+            source_plugin: source_plugin.to_string(),
+            event_type: reply_to.clone(),
+            timestamp: current_timestamp(),
+            payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            correlation_id: request.correlation_id.clone(),
+            reply_to: None,
+        });
+        Ok(())
+    }
 }
 
 impl Default for EventBus {
@@ -86,9 +321,271 @@ impl Default for EventBus {
     }
 }
 
+/// Whether an event type matches a subscription pattern
+///
+/// Segments are separated by dots. `*` matches exactly one segment, except
+/// as the last segment where it matches one or more. A pattern without `*`
+/// must equal the topic.
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.split('.').collect();
+    let topic_segments: Vec<&str> = topic.split('.').collect();
+
+    for (i, pattern_segment) in pattern_segments.iter().enumerate() {
+        let is_last = i == pattern_segments.len() - 1;
+        match topic_segments.get(i) {
+            Some(_) if *pattern_segment == "*" && is_last => return true,
+            Some(_) if *pattern_segment == "*" => continue,
+            Some(topic_segment) if topic_segment == pattern_segment => continue,
+            _ => return false,
+        }
+    }
+
+    pattern_segments.len() == topic_segments.len()
+}
+
+/// Run `handler` on `event` up to `retry.max_attempts` times
+/// Returns the last error and the number of attempts made if every attempt failed.
+/// Panics are only caught and retried where they unwind; release builds use
+/// `panic = "abort"`, so there a panicking handler ends the process.
+pub async fn handle_with_retry(handler: &EventHandler, event: &Event, retry: HandlerRetry) -> std::result::Result<(), (String, u32)> {
+    let max_attempts = retry.max_attempts.max(1);
+    let mut backoff = retry.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        let error = match std::panic::AssertUnwindSafe(handler(event.clone())).catch_unwind().await {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => e.to_string(),
+            Err(panic) => format!("handler panicked: {}", crate::bridge::panic_message(panic)),
+        };
+
+        if attempt >= max_attempts {
+            return Err((error, attempt));
+        }
+        log::warn!("⚠️  Handler for '{}' failed (attempt {}/{}), retrying in {:?}: {}", event.event_type, attempt, max_attempts, backoff, error);
+        tokio::time::sleep(backoff).await;
+        backoff = backoff.saturating_mul(2);
+        attempt += 1;
+    }
+}
+
 fn current_timestamp() -> i64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs() as i64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, n: i64) -> Event {
+        Event {
+            source_plugin: "test".to_string(),
+            event_type: event_type.to_string(),
+            timestamp: n,
+            payload: Value::from(n),
+            correlation_id: None,
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn test_history_evicts_oldest_at_capacity() {
+        let bus = EventBus::with_history_capacity(3);
+        for n in 0..5 {
+            bus.publish(event("tick", n));
+        }
+
+        let kept: Vec<i64> = bus.recent("", 10).iter().map(|e| e.timestamp).collect();
+        assert_eq!(kept, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_recent_filters_by_prefix_and_limit() {
+        let bus = EventBus::new();
+        bus.publish(event("packs.purchase_request", 1));
+        bus.publish(event("levels.xp_gained", 2));
+        bus.publish(event("packs.opened", 3));
+        bus.publish(event("packs.purchase_request", 4));
+
+        let packs: Vec<i64> = bus.recent("packs.", 10).iter().map(|e| e.timestamp).collect();
+        assert_eq!(packs, vec![1, 3, 4]);
+
+        let newest: Vec<i64> = bus.recent("packs.", 2).iter().map(|e| e.timestamp).collect();
+        assert_eq!(newest, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_history() {
+        let bus = EventBus::with_history_capacity(0);
+        bus.publish(event("tick", 1));
+        assert!(bus.recent("", 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_replay_returns_missed_then_live() {
+        let bus = EventBus::new();
+        bus.publish(event("packs.purchase_request", 1));
+        bus.publish(event("packs.opened", 2));
+        bus.publish(event("packs.purchase_request", 3));
+
+        let (missed, mut rx) = bus.subscribe_to_with_replay("packs.purchase_request", 10).await;
+        assert_eq!(missed.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![1, 3]);
+
+        bus.publish(event("packs.purchase_request", 4));
+        assert_eq!(rx.recv().await.unwrap().timestamp, 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_publish_during_subscribe_is_not_dropped() {
+        let bus = Arc::new(EventBus::with_history_capacity(0));
+        let mut rx = bus.subscribe_to("currency.*").await;
+
+        // Keep taking the pattern write lock while events are published
+        let subscriber = {
+            let bus = bus.clone();
+            tokio::spawn(async move {
+                for n in 0..500 {
+                    let _ = bus.subscribe_to(&format!("other{}.*", n)).await;
+                }
+            })
+        };
+        for n in 0..50 {
+            bus.publish(event("currency.spent", n));
+            tokio::task::yield_now().await;
+        }
+        subscriber.await.unwrap();
+
+        for n in 0..50 {
+            assert_eq!(rx.recv().await.unwrap().timestamp, n);
+        }
+    }
+
+    #[test]
+    fn test_topic_matches_patterns() {
+        assert!(topic_matches("currency.*", "currency.spent"));
+        assert!(topic_matches("currency.*", "currency.balance.updated"));
+        assert!(!topic_matches("currency.*", "currency"));
+        assert!(!topic_matches("currency.*", "levels.xp_gained"));
+
+        assert!(topic_matches("*.opened", "packs.opened"));
+        assert!(!topic_matches("*.opened", "packs.closed"));
+        assert!(!topic_matches("*.opened", "packs.box.opened"));
+
+        assert!(topic_matches("*", "anything.at.all"));
+        assert!(topic_matches("packs.opened", "packs.opened"));
+        assert!(!topic_matches("packs.opened", "packs.opened.twice"));
+    }
+
+    #[tokio::test]
+    async fn test_pattern_subscription_receives_matching_events() {
+        let bus = EventBus::new();
+        let mut currency = bus.subscribe_to("currency.*").await;
+        let mut opened = bus.subscribe_to("*.opened").await;
+        let mut exact = bus.subscribe_to("packs.opened").await;
+
+        bus.publish(event("levels.xp_gained", 1));
+        bus.publish(event("currency.spent", 2));
+        bus.publish(event("packs.opened", 3));
+
+        assert_eq!(currency.recv().await.unwrap().timestamp, 2);
+        assert!(currency.try_recv().is_err());
+        assert_eq!(opened.recv().await.unwrap().timestamp, 3);
+        assert!(opened.try_recv().is_err());
+        assert_eq!(exact.recv().await.unwrap().timestamp, 3);
+    }
+
+    #[tokio::test]
+    async fn test_request_receives_matching_reply() {
+        let bus = Arc::new(EventBus::new());
+        let mut requests = bus.subscribe_to("packs.purchase_request").await;
+
+        let responder = bus.clone();
+        tokio::spawn(async move {
+            let request = requests.recv().await.unwrap();
+            responder.reply("currency", &request, &serde_json::json!({ "ok": true })).unwrap();
+        });
+
+        let reply = bus.request("packs", "packs.purchase_request", &serde_json::json!({ "cost": 10 }), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(reply["ok"], true);
+    }
+
+    #[tokio::test]
+    async fn test_request_ignores_mismatched_correlation_id() {
+        let bus = Arc::new(EventBus::new());
+        let mut requests = bus.subscribe_to("packs.purchase_request").await;
+
+        let responder = bus.clone();
+        tokio::spawn(async move {
+            let mut request = requests.recv().await.unwrap();
+            request.correlation_id = Some("someone-else".to_string());
+            responder.reply("currency", &request, &serde_json::json!({ "ok": true })).unwrap();
+        });
+
+        let result = bus.request("packs", "packs.purchase_request", &serde_json::json!({}), Duration::from_millis(100)).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_times_out_without_reply() {
+        let bus = EventBus::new();
+        let result = bus.request("packs", "packs.purchase_request", &serde_json::json!({}), Duration::from_millis(50)).await;
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_handler_retries_then_reports_failure() {
+        let bus = EventBus::new();
+        let calls = Arc::new(AtomicU64::new(0));
+        let failures = Arc::new(Mutex::new(Vec::new()));
+
+        let handler_calls = calls.clone();
+        let handler: EventHandler = Arc::new(move |event: Event| -> BoxFuture<'static, Result<()>> {
+            let n = handler_calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                // Event 1 recovers on its second attempt, event 2 never does
+                if event.timestamp == 1 && n == 0 {
+                    return Err(anyhow!("database is locked"));
+                }
+                if event.timestamp == 2 {
+                    panic!("purchase handler bug");
+                }
+                Ok(())
+            })
+        });
+        let sink_failures = failures.clone();
+        let sink: FailureSink = Arc::new(move |event: &Event, error: &str, attempts: u32| {
+            sink_failures.lock().unwrap().push((event.timestamp, error.to_string(), attempts));
+        });
+
+        let retry = HandlerRetry { max_attempts: 2, initial_backoff: Duration::from_millis(1) };
+        let task = bus.subscribe_handler("packs", "packs.purchase_request", retry, handler, sink).await;
+        assert!(bus.handler("packs", "packs.purchase_request").is_some());
+
+        bus.publish(event("packs.purchase_request", 1));
+        bus.publish(event("packs.purchase_request", 2));
+        for _ in 0..100 {
+            if !failures.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        task.abort();
+
+        let failures = failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 2);
+        assert!(failures[0].1.contains("purchase handler bug"));
+        assert_eq!(failures[0].2, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_reply_requires_request_event() {
+        let bus = EventBus::new();
+        assert!(bus.reply("currency", &event("packs.opened", 1), &serde_json::json!({})).is_err());
+    }
+}
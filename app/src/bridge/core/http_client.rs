@@ -0,0 +1,235 @@
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+/// Shared client for plugins' outbound requests (one connection pool per process)
+static SHARED: Lazy<HttpClient> = Lazy::new(|| {
+    HttpClient::new(HttpClientConfig::default()).expect("Failed to build shared HTTP client")
+});
+
+/// Timeouts and user-agent for an `HttpClient`
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    pub connect_timeout: Duration,
+    /// Whole-request timeout, including reading the response body
+    pub timeout: Duration,
+    pub user_agent: String,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            user_agent: format!("WebArcade/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+/// When `send_with_retry` tries a request again
+///
+/// Connection errors, timeouts, 429 and 5xx responses are retried with
+/// exponential backoff; a `Retry-After` header (in seconds) is honoured up to
+/// `max_backoff`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0 = no retry)
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// Delay before retry number `attempt` (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Outbound HTTP client with sane defaults for integration plugins
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+}
+
+impl HttpClient {
+    pub fn new(config: HttpClientConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.timeout)
+            .user_agent(config.user_agent)
+            .build()
+            .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))?;
+        Ok(Self { client })
+    }
+
+    /// The underlying `reqwest::Client` (cheap to clone, shares the pool)
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    pub fn put(&self, url: &str) -> RequestBuilder {
+        self.client.put(url)
+    }
+
+    pub fn delete(&self, url: &str) -> RequestBuilder {
+        self.client.delete(url)
+    }
+
+    /// Send `request`, retrying transient failures per `policy`
+    ///
+    /// Returns the last response once retries run out, even if it is a 5xx,
+    /// so callers can still inspect it. Requests with streaming bodies can't
+    /// be cloned and are sent once.
+    pub async fn send_with_retry(&self, request: RequestBuilder, policy: RetryPolicy) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let Some(this_try) = request.try_clone() else {
+                return Ok(request.send().await?);
+            };
+
+            let retries_left = attempt < policy.max_retries;
+            let delay = match this_try.send().await {
+                Ok(response) if retries_left && is_retryable_status(response.status()) => {
+                    retry_after(&response).map(|d| d.min(policy.max_backoff))
+                        .unwrap_or_else(|| policy.backoff(attempt + 1))
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if retries_left && (e.is_connect() || e.is_timeout()) => policy.backoff(attempt + 1),
+                Err(e) => return Err(e.into()),
+            };
+
+            attempt += 1;
+            log::debug!("Retrying outbound request (attempt {}/{}) in {:?}", attempt, policy.max_retries, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// The process-wide client used by `PluginContext::http`
+pub fn shared() -> &'static HttpClient {
+    &SHARED
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str().ok()?
+        .trim()
+        .parse::<u64>().ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use hyper::server::conn::http1;
+    use hyper::service::service_fn;
+    use hyper_util::rt::TokioIo;
+    use tokio::net::TcpListener;
+    use crate::bridge::core::router_utils::full_body;
+
+    /// Server that answers 503 to the first `failures` requests and 200 after,
+    /// each after `delay`
+    async fn mock_server(failures: usize, delay: Duration, hits: Arc<AtomicUsize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let hits = hits.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
+                        let hits = hits.clone();
+                        async move {
+                            let n = hits.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(delay).await;
+                            let status = if n < failures { 503 } else { 200 };
+                            let agent = req.headers().get("user-agent").and_then(|v| v.to_str().ok()).unwrap_or("").to_string();
+                            Ok::<_, Infallible>(hyper::Response::builder().status(status).body(full_body(&agent)).unwrap())
+                        }
+                    });
+                    let _ = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await;
+                });
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn fast_retries(max_retries: u32) -> RetryPolicy {
+        RetryPolicy { max_retries, initial_backoff: Duration::from_millis(5), max_backoff: Duration::from_millis(20) }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(250));
+        assert_eq!(policy.backoff(3), Duration::from_secs(1));
+        assert_eq!(policy.backoff(30), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors_until_success() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let url = mock_server(2, Duration::ZERO, hits.clone()).await;
+        let client = HttpClient::new(HttpClientConfig::default()).unwrap();
+
+        let response = client.send_with_retry(client.get(&url), fast_retries(3)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        assert!(response.text().await.unwrap().starts_with("WebArcade/"));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let url = mock_server(10, Duration::ZERO, hits.clone()).await;
+        let client = HttpClient::new(HttpClientConfig::default()).unwrap();
+
+        let response = client.send_with_retry(client.get(&url), fast_retries(2)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_times_out_slow_responses() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let url = mock_server(0, Duration::from_millis(500), hits.clone()).await;
+        let client = HttpClient::new(HttpClientConfig { timeout: Duration::from_millis(100), ..Default::default() }).unwrap();
+
+        let err = client.send_with_retry(client.get(&url), fast_retries(1)).await.unwrap_err();
+        assert!(err.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+}
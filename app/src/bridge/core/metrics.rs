@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use crate::bridge::core::database;
+use crate::bridge::core::websocket_bridge::WebSocketBridge;
+
+/// Upper bounds (seconds) of the request latency histogram buckets
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Process-wide metrics, rendered by `GET /metrics`
+static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+/// Record one handled API request
+pub fn record_request(plugin: &str, method: &str, status: u16, duration: Duration) {
+    METRICS.record(plugin, method, status, duration);
+}
+
+/// Record the size of one payload exchanged with a plugin handler
+///
+/// `direction` is "request" (raw body), "context" (the JSON request context
+/// handed to the DLL, body base64-encoded) or "response" (JSON from the DLL).
+pub fn record_payload_size(plugin: &str, route: &str, direction: &'static str, bytes: u64) {
+    METRICS.record_payload(plugin, route, direction, bytes);
+}
+
+/// Render every metric in the Prometheus text exposition format
+pub fn render() -> String {
+    let mut out = METRICS.render();
+
+    let _ = writeln!(out, "# HELP bridge_websocket_connections Open WebSocket connections");
+    let _ = writeln!(out, "# TYPE bridge_websocket_connections gauge");
+    let _ = writeln!(out, "bridge_websocket_connections {}", WebSocketBridge::connection_count());
+
+    let pools = database::pool_states();
+    let _ = writeln!(out, "# HELP bridge_db_pool_connections Open connections per database pool");
+    let _ = writeln!(out, "# TYPE bridge_db_pool_connections gauge");
+    for (db, state) in &pools {
+        let _ = writeln!(out, "bridge_db_pool_connections{{db=\"{}\"}} {}", escape_label(db), state.connections);
+    }
+    let _ = writeln!(out, "# HELP bridge_db_pool_idle_connections Idle connections per database pool");
+    let _ = writeln!(out, "# TYPE bridge_db_pool_idle_connections gauge");
+    for (db, state) in &pools {
+        let _ = writeln!(out, "bridge_db_pool_idle_connections{{db=\"{}\"}} {}", escape_label(db), state.idle_connections);
+    }
+
+    out
+}
+
+/// Latency histogram for one plugin
+#[derive(Default)]
+struct Histogram {
+    /// Non-cumulative count per bucket in `LATENCY_BUCKETS`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+/// Running totals of payload sizes for one plugin route and direction
+#[derive(Default)]
+struct SizeStats {
+    count: u64,
+    sum: u64,
+    max: u64,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    /// (plugin, method, status) -> request count
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// plugin -> latency histogram
+    latency: BTreeMap<String, Histogram>,
+    /// (plugin, route pattern, direction) -> payload sizes
+    payloads: BTreeMap<(String, String, &'static str), SizeStats>,
+}
+
+/// Request counters and latency histograms
+struct Metrics {
+    inner: Mutex<MetricsInner>,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self { inner: Mutex::new(MetricsInner::default()) }
+    }
+
+    fn record(&self, plugin: &str, method: &str, status: u16, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let mut inner = self.inner.lock().unwrap();
+
+        *inner.requests
+            .entry((plugin.to_string(), method.to_string(), status))
+            .or_insert(0) += 1;
+
+        let histogram = inner.latency.entry(plugin.to_string()).or_default();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|le| seconds <= *le) {
+            histogram.buckets[bucket] += 1;
+        }
+        histogram.count += 1;
+        histogram.sum += seconds;
+    }
+
+    fn record_payload(&self, plugin: &str, route: &str, direction: &'static str, bytes: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        let stats = inner.payloads
+            .entry((plugin.to_string(), route.to_string(), direction))
+            .or_default();
+        stats.count += 1;
+        stats.sum += bytes;
+        stats.max = stats.max.max(bytes);
+    }
+
+    fn render(&self) -> String {
+        let inner = self.inner.lock().unwrap();
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP bridge_http_requests_total API requests handled, by plugin, method and status");
+        let _ = writeln!(out, "# TYPE bridge_http_requests_total counter");
+        for ((plugin, method, status), count) in &inner.requests {
+            let _ = writeln!(
+                out,
+                "bridge_http_requests_total{{plugin=\"{}\",method=\"{}\",status=\"{}\"}} {}",
+                escape_label(plugin), escape_label(method), status, count
+            );
+        }
+
+        let _ = writeln!(out, "# HELP bridge_http_request_duration_seconds API request latency, by plugin");
+        let _ = writeln!(out, "# TYPE bridge_http_request_duration_seconds histogram");
+        for (plugin, histogram) in &inner.latency {
+            let plugin = escape_label(plugin);
+            let mut cumulative = 0;
+            for (le, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(out, "bridge_http_request_duration_seconds_bucket{{plugin=\"{}\",le=\"{}\"}} {}", plugin, le, cumulative);
+            }
+            let _ = writeln!(out, "bridge_http_request_duration_seconds_bucket{{plugin=\"{}\",le=\"+Inf\"}} {}", plugin, histogram.count);
+            let _ = writeln!(out, "bridge_http_request_duration_seconds_sum{{plugin=\"{}\"}} {}", plugin, histogram.sum);
+            let _ = writeln!(out, "bridge_http_request_duration_seconds_count{{plugin=\"{}\"}} {}", plugin, histogram.count);
+        }
+
+        let _ = writeln!(out, "# HELP bridge_plugin_payload_bytes Bytes exchanged with plugin handlers, by plugin, route and direction");
+        let _ = writeln!(out, "# TYPE bridge_plugin_payload_bytes summary");
+        for ((plugin, route, direction), stats) in &inner.payloads {
+            let labels = format!("plugin=\"{}\",route=\"{}\",direction=\"{}\"", escape_label(plugin), escape_label(route), direction);
+            let _ = writeln!(out, "bridge_plugin_payload_bytes_sum{{{}}} {}", labels, stats.sum);
+            let _ = writeln!(out, "bridge_plugin_payload_bytes_count{{{}}} {}", labels, stats.count);
+        }
+        let _ = writeln!(out, "# HELP bridge_plugin_payload_max_bytes Largest payload seen, by plugin, route and direction");
+        let _ = writeln!(out, "# TYPE bridge_plugin_payload_max_bytes gauge");
+        for ((plugin, route, direction), stats) in &inner.payloads {
+            let _ = writeln!(
+                out,
+                "bridge_plugin_payload_max_bytes{{plugin=\"{}\",route=\"{}\",direction=\"{}\"}} {}",
+                escape_label(plugin), escape_label(route), direction, stats.max
+            );
+        }
+
+        out
+    }
+}
+
+/// Escape a label value per the exposition format (backslash, quote, newline)
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_requests_and_fills_cumulative_buckets() {
+        let metrics = Metrics::new();
+        metrics.record("currency", "GET", 200, Duration::from_millis(3));
+        metrics.record("currency", "GET", 200, Duration::from_millis(40));
+        metrics.record("currency", "POST", 500, Duration::from_secs(30));
+
+        let out = metrics.render();
+        assert!(out.contains("bridge_http_requests_total{plugin=\"currency\",method=\"GET\",status=\"200\"} 2"));
+        assert!(out.contains("bridge_http_requests_total{plugin=\"currency\",method=\"POST\",status=\"500\"} 1"));
+        assert!(out.contains("bridge_http_request_duration_seconds_bucket{plugin=\"currency\",le=\"0.005\"} 1"));
+        assert!(out.contains("bridge_http_request_duration_seconds_bucket{plugin=\"currency\",le=\"0.05\"} 2"));
+        assert!(out.contains("bridge_http_request_duration_seconds_bucket{plugin=\"currency\",le=\"10\"} 2"));
+        assert!(out.contains("bridge_http_request_duration_seconds_bucket{plugin=\"currency\",le=\"+Inf\"} 3"));
+        assert!(out.contains("bridge_http_request_duration_seconds_count{plugin=\"currency\"} 3"));
+    }
+
+    #[test]
+    fn test_payload_sizes_track_sum_count_and_max() {
+        let metrics = Metrics::new();
+        metrics.record_payload("files", "/upload", "request", 1000);
+        metrics.record_payload("files", "/upload", "context", 1400);
+        metrics.record_payload("files", "/upload", "request", 3000);
+
+        let out = metrics.render();
+        assert!(out.contains("bridge_plugin_payload_bytes_sum{plugin=\"files\",route=\"/upload\",direction=\"request\"} 4000"));
+        assert!(out.contains("bridge_plugin_payload_bytes_count{plugin=\"files\",route=\"/upload\",direction=\"request\"} 2"));
+        assert!(out.contains("bridge_plugin_payload_max_bytes{plugin=\"files\",route=\"/upload\",direction=\"request\"} 3000"));
+        assert!(out.contains("bridge_plugin_payload_max_bytes{plugin=\"files\",route=\"/upload\",direction=\"context\"} 1400"));
+    }
+
+    #[test]
+    fn test_escapes_label_values() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,4 +1,5 @@
 pub mod events;
+pub mod database;
 pub mod services;
 pub mod plugin;
 pub mod plugin_context;
@@ -9,13 +10,24 @@ pub mod router_utils;
 pub mod plugin_macros;
 pub mod dynamic_plugin_loader;
 pub mod plugin_exports;
+pub mod metrics;
+pub mod scheduler;
+pub mod oauth;
+pub mod secrets;
+pub mod http_client;
+pub mod rate_limit;
+pub mod dead_letters;
 
-pub use events::{Event, EventBus};
-pub use services::ServiceRegistry;
+pub use events::{Event, EventBus, HandlerRetry};
+pub use dead_letters::DeadLetter;
+pub use services::{ServiceRegistry, ServiceError, Pagination};
 pub use plugin::{Plugin, PluginMetadata};
 pub use plugin_context::PluginContext;
 pub use plugin_manager::PluginManager;
 pub use websocket_bridge::WebSocketBridge;
-pub use plugin_router::{PluginRouter, RouterRegistry};
+pub use plugin_router::{PluginRouter, RouterRegistry, RouteInfo};
 pub use router_utils::*;
-pub use dynamic_plugin_loader::{DynamicPluginLoader, PluginInfo};
+pub use http_client::{HttpClient, HttpClientConfig, RetryPolicy};
+pub use rate_limit::{LimitPolicy, RateLimitConfig, RateLimited};
+pub use oauth::{OAuthClient, OAuthConfig, OAuthTokens};
+pub use dynamic_plugin_loader::{DynamicPluginLoader, PluginInfo, PluginListing, PluginStatus};
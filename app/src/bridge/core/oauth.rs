@@ -0,0 +1,505 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use chrono::Utc;
+use http_body_util::combinators::BoxBody;
+use hyper::body::Bytes;
+use hyper::{Method, Response, StatusCode};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::bridge::core::database;
+use crate::bridge::core::http_client;
+use crate::bridge::core::plugin_router::PluginRouter;
+use crate::bridge::core::secrets::{self, SecretKeys};
+use crate::bridge::core::router_utils::{error_response, full_body, parse_query_param};
+
+/// How long an authorize `state` stays valid
+const STATE_TTL_SECS: i64 = 600;
+
+/// Refresh access tokens this many seconds before they expire
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// OAuth2 authorization-code settings for one integration (usually part of the plugin config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthConfig {
+    pub authorize_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Must match the redirect URI registered with the provider,
+    /// e.g. `http://127.0.0.1:3001/withings/auth/callback`
+    pub redirect_uri: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Extra form fields sent to the token endpoint (Withings wants `action=requesttoken`)
+    #[serde(default)]
+    pub extra_token_params: BTreeMap<String, String>,
+}
+
+/// Tokens stored for a plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthTokens {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix timestamp (seconds), `None` if the provider didn't say
+    pub expires_at: Option<i64>,
+    pub scope: Option<String>,
+}
+
+impl OAuthTokens {
+    /// Whether the access token expires within `margin_secs` of `now`
+    pub fn expires_within(&self, now: i64, margin_secs: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at - margin_secs <= now)
+    }
+}
+
+/// Authorization-code flow for one plugin: authorize URL, callback, token storage and refresh
+/// Tokens live in `_oauth_tokens` keyed by plugin id, encrypted via `secrets`.
+pub struct OAuthClient {
+    plugin_id: String,
+    config: OAuthConfig,
+    db_path: String,
+    http: reqwest::Client,
+    /// Overrides the process-wide keys (loaded lazily, since that touches the keychain)
+    keys: Option<&'static SecretKeys>,
+}
+
+impl OAuthClient {
+    pub fn new(plugin_id: &str, config: OAuthConfig, db_path: &str) -> Self {
+        Self {
+            plugin_id: plugin_id.to_string(),
+            config,
+            db_path: db_path.to_string(),
+            http: http_client::shared().client().clone(),
+            keys: None,
+        }
+    }
+
+    /// Seal tokens with `keys` instead of the process-wide keys
+    pub fn with_keys(mut self, keys: &'static SecretKeys) -> Self {
+        self.keys = Some(keys);
+        self
+    }
+
+    fn keys(&self) -> &'static SecretKeys {
+        self.keys.unwrap_or_else(secrets::keys)
+    }
+
+    /// Build the provider's authorize URL with a fresh CSRF `state`
+    pub fn authorize_url(&self) -> Result<String> {
+        let state = random_state();
+        let conn = database::get_pool(&self.db_path)?.get()?;
+        ensure_oauth_tables(&conn)?;
+        conn.execute(
+            "DELETE FROM _oauth_states WHERE created_at < ?1",
+            [Utc::now().timestamp() - STATE_TTL_SECS],
+        )?;
+        conn.execute(
+            "INSERT INTO _oauth_states (state, plugin_id, created_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![state, self.plugin_id, Utc::now().timestamp()],
+        )?;
+
+        let mut url = url::Url::parse(&self.config.authorize_url)
+            .map_err(|e| anyhow!("Invalid authorize_url for plugin '{}': {}", self.plugin_id, e))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("response_type", "code");
+            query.append_pair("client_id", &self.config.client_id);
+            query.append_pair("redirect_uri", &self.config.redirect_uri);
+            if !self.config.scopes.is_empty() {
+                query.append_pair("scope", &self.config.scopes.join(" "));
+            }
+            query.append_pair("state", &state);
+        }
+        Ok(url.into())
+    }
+
+    /// Validate `state`, exchange `code` for tokens and store them
+    ///
+    /// A state is single-use and expires after ten minutes.
+    pub async fn handle_callback(&self, code: &str, state: &str) -> Result<OAuthTokens> {
+        self.consume_state(state)?;
+
+        let mut form = vec![
+            ("grant_type".to_string(), "authorization_code".to_string()),
+            ("code".to_string(), code.to_string()),
+            ("redirect_uri".to_string(), self.config.redirect_uri.clone()),
+        ];
+        let tokens = self.request_tokens(&mut form, None).await?;
+        self.store_tokens(&tokens)?;
+        log::info!("🔑 [{}] OAuth authorization complete", self.plugin_id);
+        Ok(tokens)
+    }
+
+    /// Stored tokens, `None` until the user has gone through the flow
+    pub fn tokens(&self) -> Result<Option<OAuthTokens>> {
+        let conn = database::get_pool(&self.db_path)?.get()?;
+        load_tokens(&conn, self.keys(), &self.plugin_id)
+    }
+
+    /// A valid access token, refreshing it first if it is about to expire
+    pub async fn access_token(&self) -> Result<String> {
+        let tokens = self.tokens()?
+            .ok_or_else(|| anyhow!("Plugin '{}' is not authorized yet", self.plugin_id))?;
+
+        if !tokens.expires_within(Utc::now().timestamp(), REFRESH_MARGIN_SECS) {
+            return Ok(tokens.access_token);
+        }
+        Ok(self.refresh(&tokens).await?.access_token)
+    }
+
+    /// Exchange the stored refresh token for new tokens
+    pub async fn refresh(&self, current: &OAuthTokens) -> Result<OAuthTokens> {
+        let refresh_token = current.refresh_token.clone()
+            .ok_or_else(|| anyhow!("Plugin '{}' has no refresh token; authorize again", self.plugin_id))?;
+
+        let mut form = vec![
+            ("grant_type".to_string(), "refresh_token".to_string()),
+            ("refresh_token".to_string(), refresh_token.clone()),
+        ];
+        let tokens = self.request_tokens(&mut form, Some(refresh_token)).await?;
+        self.store_tokens(&tokens)?;
+        log::info!("🔄 [{}] OAuth access token refreshed", self.plugin_id);
+        Ok(tokens)
+    }
+
+    /// Forget the stored tokens (disconnect the integration)
+    pub fn revoke(&self) -> Result<bool> {
+        let conn = database::get_pool(&self.db_path)?.get()?;
+        ensure_oauth_tables(&conn)?;
+        Ok(conn.execute("DELETE FROM _oauth_tokens WHERE plugin_id = ?1", [&self.plugin_id])? > 0)
+    }
+
+    /// Add `GET /auth/start` (redirects to the provider) and `GET /auth/callback` to `router`
+    pub fn register_routes(self: &Arc<Self>, router: &mut PluginRouter) {
+        let client = self.clone();
+        router.route(Method::GET, "/auth/start", move |_path, _query, _req| {
+            let client = client.clone();
+            Box::pin(async move {
+                match client.authorize_url() {
+                    Ok(url) => redirect_response(&url),
+                    Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+                }
+            })
+        });
+
+        let client = self.clone();
+        router.route(Method::GET, "/auth/callback", move |_path, query, _req| {
+            let client = client.clone();
+            Box::pin(async move {
+                if let Some(error) = parse_query_param(&query, "error") {
+                    return error_response(StatusCode::BAD_REQUEST, &format!("Authorization denied: {}", error));
+                }
+                let (Some(code), Some(state)) = (parse_query_param(&query, "code"), parse_query_param(&query, "state")) else {
+                    return error_response(StatusCode::BAD_REQUEST, "Missing code or state");
+                };
+                match client.handle_callback(&code, &state).await {
+                    Ok(_) => connected_response(&client.plugin_id),
+                    Err(e) => {
+                        log::warn!("⚠️  [{}] OAuth callback failed: {}", client.plugin_id, e);
+                        error_response(StatusCode::BAD_REQUEST, &e.to_string())
+                    }
+                }
+            })
+        });
+    }
+
+    fn consume_state(&self, state: &str) -> Result<()> {
+        let conn = database::get_pool(&self.db_path)?.get()?;
+        ensure_oauth_tables(&conn)?;
+        let created_at: Option<i64> = conn.query_row(
+            "DELETE FROM _oauth_states WHERE state = ?1 AND plugin_id = ?2 RETURNING created_at",
+            [state, &self.plugin_id],
+            |row| row.get(0),
+        ).map(Some).or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e),
+        })?;
+
+        match created_at {
+            Some(created_at) if Utc::now().timestamp() - created_at <= STATE_TTL_SECS => Ok(()),
+            Some(_) => Err(anyhow!("OAuth state expired; start the authorization again")),
+            None => Err(anyhow!("Unknown OAuth state")),
+        }
+    }
+
+    async fn request_tokens(&self, form: &mut Vec<(String, String)>, previous_refresh: Option<String>) -> Result<OAuthTokens> {
+        form.push(("client_id".to_string(), self.config.client_id.clone()));
+        form.push(("client_secret".to_string(), self.config.client_secret.clone()));
+        form.extend(self.config.extra_token_params.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        let response = self.http.post(&self.config.token_url)
+            .header("Accept", "application/json")
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Token request failed: {}", e))?;
+
+        let status = response.status();
+        let body: Value = response.json().await
+            .map_err(|e| anyhow!("Token endpoint returned invalid JSON: {}", e))?;
+        if !status.is_success() {
+            return Err(anyhow!("Token endpoint returned {}: {}", status, body));
+        }
+
+        let mut tokens = parse_token_response(&body, Utc::now().timestamp())?;
+        // Providers may omit the refresh token on refresh, meaning "keep the old one"
+        if tokens.refresh_token.is_none() {
+            tokens.refresh_token = previous_refresh;
+        }
+        Ok(tokens)
+    }
+
+    fn store_tokens(&self, tokens: &OAuthTokens) -> Result<()> {
+        let conn = database::get_pool(&self.db_path)?.get()?;
+        ensure_oauth_tables(&conn)?;
+        conn.execute(
+            "INSERT INTO _oauth_tokens (plugin_id, access_token, refresh_token, expires_at, scope, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(plugin_id) DO UPDATE SET
+                access_token = excluded.access_token,
+                refresh_token = excluded.refresh_token,
+                expires_at = excluded.expires_at,
+                scope = excluded.scope,
+                updated_at = excluded.updated_at",
+            rusqlite::params![
+                self.plugin_id,
+                self.keys().seal(&format!("{}/access_token", self.plugin_id), &tokens.access_token)?,
+                tokens.refresh_token.as_deref()
+                    .map(|token| self.keys().seal(&format!("{}/refresh_token", self.plugin_id), token))
+                    .transpose()?,
+                tokens.expires_at,
+                tokens.scope,
+                Utc::now().timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn ensure_oauth_tables(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _oauth_tokens (
+            plugin_id TEXT PRIMARY KEY,
+            access_token TEXT NOT NULL,
+            refresh_token TEXT,
+            expires_at INTEGER,
+            scope TEXT,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS _oauth_states (
+            state TEXT PRIMARY KEY,
+            plugin_id TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+fn load_tokens(conn: &rusqlite::Connection, keys: &SecretKeys, plugin_id: &str) -> Result<Option<OAuthTokens>> {
+    ensure_oauth_tables(conn)?;
+    let result = conn.query_row(
+        "SELECT access_token, refresh_token, expires_at, scope FROM _oauth_tokens WHERE plugin_id = ?1",
+        [plugin_id],
+        |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<i64>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        )),
+    );
+    let (access_token, refresh_token, expires_at, scope) = match result {
+        Ok(row) => row,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(OAuthTokens {
+        access_token: open_token(keys, plugin_id, "access_token", access_token)?,
+        refresh_token: refresh_token.map(|token| open_token(keys, plugin_id, "refresh_token", token)).transpose()?,
+        expires_at,
+        scope,
+    }))
+}
+
+/// Decrypt a stored token; rows written before encryption are still plaintext
+/// and get sealed the next time the tokens are stored
+fn open_token(keys: &SecretKeys, plugin_id: &str, name: &str, stored: String) -> Result<String> {
+    if secrets::is_sealed(&stored) {
+        keys.open(&format!("{}/{}", plugin_id, name), &stored)
+    } else {
+        Ok(stored)
+    }
+}
+
+/// Parse a token endpoint response
+/// Accepts the standard shape and Withings' `{ "status": 0, "body": { ... } }` wrapper.
+fn parse_token_response(body: &Value, now: i64) -> Result<OAuthTokens> {
+    let body = match body.get("body") {
+        Some(inner) if body.get("access_token").is_none() => inner,
+        _ => body,
+    };
+
+    let access_token = body.get("access_token").and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("Token response has no access_token: {}", body))?;
+    let expires_in = body.get("expires_in")
+        .and_then(|v| v.as_i64().or_else(|| v.as_str()?.parse().ok()));
+
+    Ok(OAuthTokens {
+        access_token: access_token.to_string(),
+        refresh_token: body.get("refresh_token").and_then(Value::as_str).map(str::to_string),
+        expires_at: expires_in.map(|secs| now + secs),
+        scope: body.get("scope").and_then(Value::as_str).map(str::to_string),
+    })
+}
+
+fn random_state() -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn redirect_response(location: &str) -> Response<BoxBody<Bytes, Infallible>> {
+    Response::builder()
+        .status(StatusCode::FOUND)
+        .header("Location", location)
+        .header("Cache-Control", "no-store")
+        .body(full_body(""))
+        .unwrap()
+}
+
+fn connected_response(plugin_id: &str) -> Response<BoxBody<Bytes, Infallible>> {
+    let html = format!(
+        "<!DOCTYPE html><html><body><p>{} is connected. You can close this window.</p></body></html>",
+        plugin_id
+    );
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(full_body(&html))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use hyper::server::conn::http1;
+    use hyper::service::service_fn;
+    use hyper_util::rt::TokioIo;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    /// Token endpoint that records each form body and answers with a fixed token
+    async fn mock_token_endpoint(requests: Arc<Mutex<Vec<String>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let requests = requests.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
+                        let requests = requests.clone();
+                        async move {
+                            let body = req.collect().await.unwrap().to_bytes();
+                            let form = String::from_utf8_lossy(&body).into_owned();
+                            let n = {
+                                let mut requests = requests.lock().unwrap();
+                                requests.push(form);
+                                requests.len()
+                            };
+                            let json = serde_json::json!({
+                                "status": 0,
+                                "body": { "access_token": format!("access-{}", n), "refresh_token": "refresh-1", "expires_in": 10800 }
+                            });
+                            Ok::<_, Infallible>(Response::new(full_body(&json.to_string())))
+                        }
+                    });
+                    let _ = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await;
+                });
+            }
+        });
+        format!("http://{}/oauth2/token", addr)
+    }
+
+    fn test_config(token_url: String) -> OAuthConfig {
+        OAuthConfig {
+            authorize_url: "https://account.example.com/oauth2_user/authorize2".to_string(),
+            token_url,
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: "http://127.0.0.1:3001/withings/auth/callback".to_string(),
+            scopes: vec!["user.metrics".to_string()],
+            extra_token_params: BTreeMap::from([("action".to_string(), "requesttoken".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_parse_token_response_shapes() {
+        let standard = serde_json::json!({ "access_token": "a", "refresh_token": "r", "expires_in": 3600, "scope": "read" });
+        let tokens = parse_token_response(&standard, 1000).unwrap();
+        assert_eq!(tokens.expires_at, Some(4600));
+        assert_eq!(tokens.scope.as_deref(), Some("read"));
+
+        let wrapped = serde_json::json!({ "status": 0, "body": { "access_token": "a", "expires_in": "60" } });
+        let tokens = parse_token_response(&wrapped, 1000).unwrap();
+        assert_eq!(tokens.access_token, "a");
+        assert_eq!(tokens.expires_at, Some(1060));
+        assert!(tokens.expires_within(1001, 60));
+
+        assert!(parse_token_response(&serde_json::json!({ "error": "invalid_grant" }), 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_callback_validates_state_and_exchanges_code() {
+        let db_path = std::env::temp_dir()
+            .join(format!("webarcade_oauth_test_{}.db", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let _ = std::fs::remove_file(&db_path);
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let token_url = mock_token_endpoint(requests.clone()).await;
+        let keys: &'static SecretKeys = Box::leak(Box::new(SecretKeys::new(Some([1u8; 32]), [2u8; 32])));
+        let client = OAuthClient::new("withings", test_config(token_url), &db_path).with_keys(keys);
+
+        let url = url::Url::parse(&client.authorize_url().unwrap()).unwrap();
+        let state = url.query_pairs().find(|(k, _)| k == "state").unwrap().1.into_owned();
+        assert!(url.query_pairs().any(|(k, v)| k == "scope" && v == "user.metrics"));
+
+        // Forged state never reaches the token endpoint
+        assert!(client.handle_callback("code-1", "forged").await.is_err());
+        assert!(requests.lock().unwrap().is_empty());
+
+        let tokens = client.handle_callback("code-1", &state).await.unwrap();
+        assert_eq!(tokens.access_token, "access-1");
+        assert_eq!(client.tokens().unwrap(), Some(tokens.clone()));
+        let stored: String = database::get_pool(&db_path).unwrap().get().unwrap()
+            .query_row("SELECT access_token FROM _oauth_tokens", [], |row| row.get(0)).unwrap();
+        assert!(secrets::is_sealed(&stored));
+        let form = requests.lock().unwrap()[0].clone();
+        assert!(form.contains("grant_type=authorization_code"));
+        assert!(form.contains("code=code-1"));
+        assert!(form.contains("action=requesttoken"));
+
+        // States are single-use
+        assert!(client.handle_callback("code-1", &state).await.is_err());
+
+        // Still fresh, so no refresh
+        assert_eq!(client.access_token().await.unwrap(), "access-1");
+
+        let refreshed = client.refresh(&tokens).await.unwrap();
+        assert_eq!(refreshed.access_token, "access-2");
+        assert!(requests.lock().unwrap()[1].contains("grant_type=refresh_token"));
+        assert_eq!(client.access_token().await.unwrap(), "access-2");
+
+        assert!(client.revoke().unwrap());
+        assert!(client.access_token().await.is_err());
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
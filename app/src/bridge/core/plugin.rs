@@ -34,7 +34,8 @@ pub trait Plugin: Send + Sync {
     }
 
     /// Stop plugin (cleanup, disconnect)
-    /// Called during server shutdown
+    /// Called during server shutdown and before `PluginManager::reload`, so it
+    /// must cancel any background tasks spawned in `start`
     async fn stop(&self) -> Result<()> {
         Ok(())
     }
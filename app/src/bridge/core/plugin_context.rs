@@ -1,12 +1,22 @@
 use std::sync::Arc;
 use std::future::Future;
-use anyhow::Result;
-use serde::Serialize;
+use futures_util::future::BoxFuture;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use tokio::sync::broadcast;
-use crate::bridge::core::events::{Event, EventBus};
+use crate::bridge::core::events::{self, Event, EventBus, EventHandler, FailureSink, HandlerRetry};
+use crate::bridge::core::dead_letters::{self, DeadLetter};
 use crate::bridge::core::services::ServiceRegistry;
 use crate::bridge::core::plugin_router::{PluginRouter, RouterRegistry};
+use crate::bridge::core::database::{self, DbConnection, Migration};
+use crate::bridge::core::scheduler;
+use crate::bridge::core::oauth::{OAuthClient, OAuthConfig};
+use crate::bridge::core::secrets;
+use crate::bridge::core::http_client::{self, HttpClient};
+use crate::bridge::core::rate_limit::{self, LimitPolicy, RateLimitConfig};
 
 /// Plugin context - API provided to plugins
 #[derive(Clone)]
@@ -15,6 +25,8 @@ pub struct PluginContext {
     event_bus: Arc<EventBus>,
     service_registry: Arc<ServiceRegistry>,
     router_registry: RouterRegistry,
+    db_path: String,
+    config: Value,
 }
 
 impl PluginContext {
@@ -23,21 +35,204 @@ impl PluginContext {
         event_bus: Arc<EventBus>,
         service_registry: Arc<ServiceRegistry>,
         router_registry: RouterRegistry,
-        _db_path: String, // Keep for API compatibility but unused
+        db_path: String,
     ) -> Self {
         Self {
             plugin_id,
             event_bus,
             service_registry,
             router_registry,
+            db_path,
+            config: Value::Null,
         }
     }
 
+    /// Attach the plugin's config blob (the `config` entry in webarcade.config.json)
+    pub fn with_config(mut self, config: Value) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Get plugin ID
     pub fn plugin_id(&self) -> &str {
         &self.plugin_id
     }
 
+    // ==================== Database ====================
+
+    /// Get a pooled connection to the app database (shared WAL-mode pool, returned on drop)
+    pub fn db(&self) -> Result<DbConnection> {
+        let pool = database::get_pool(&self.db_path)?;
+        Ok(pool.get()?)
+    }
+
+    /// Run `f` with a pooled connection and this plugin's id on a blocking thread
+    async fn with_db<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut DbConnection, &str) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db_path = self.db_path.clone();
+        let plugin_id = self.plugin_id.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = database::get_pool(&db_path)?.get()?;
+            f(&mut conn, &plugin_id)
+        }).await?
+    }
+
+    /// Run `f` in a database transaction on a blocking thread (commits on Ok, rolls back on Err)
+    pub async fn transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&rusqlite::Transaction) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.with_db(move |conn, _| database::run_in_transaction(conn, f)).await
+    }
+
+    /// Apply this plugin's pending schema migrations, in version order
+    pub async fn migrate_versioned(&self, migrations: &[Migration]) -> Result<Vec<i64>> {
+        let migrations = migrations.to_vec();
+        self.with_db(move |conn, plugin_id| database::migrate(conn, plugin_id, &migrations)).await
+    }
+
+    /// Revert this plugin's migrations above `target_version`, newest first
+    pub async fn rollback_migrations(&self, migrations: &[Migration], target_version: i64) -> Result<Vec<i64>> {
+        let migrations = migrations.to_vec();
+        self.with_db(move |conn, plugin_id| database::rollback(conn, plugin_id, &migrations, target_version)).await
+    }
+
+    // ==================== Settings ====================
+
+    /// Get one of this plugin's settings, `None` if it was never set
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let key = key.to_string();
+        self.with_db(move |conn, plugin_id| database::get_setting(conn, plugin_id, &key)).await
+    }
+
+    /// Get a setting, falling back to `default` when it was never set
+    pub async fn get_setting_or(&self, key: &str, default: &str) -> Result<String> {
+        Ok(self.get_setting(key).await?.unwrap_or_else(|| default.to_string()))
+    }
+
+    /// Set one of this plugin's settings
+    pub async fn set_setting(&self, key: &str, value: impl ToString) -> Result<()> {
+        let (key, value) = (key.to_string(), value.to_string());
+        self.with_db(move |conn, plugin_id| database::set_setting(conn, plugin_id, &key, &value)).await
+    }
+
+    /// Get a setting as a bool ("true"/"false", "1"/"0")
+    pub async fn get_setting_bool(&self, key: &str) -> Result<Option<bool>> {
+        match self.get_setting(key).await? {
+            Some(value) => match value.trim() {
+                "true" | "1" => Ok(Some(true)),
+                "false" | "0" => Ok(Some(false)),
+                other => Err(anyhow!("Setting '{}' is not a bool: {}", key, other)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Get a setting as an integer
+    pub async fn get_setting_i64(&self, key: &str) -> Result<Option<i64>> {
+        self.get_setting(key).await?
+            .map(|value| value.trim().parse().map_err(|e| anyhow!("Setting '{}' is not an integer: {}", key, e)))
+            .transpose()
+    }
+
+    /// Get a setting as a float
+    pub async fn get_setting_f64(&self, key: &str) -> Result<Option<f64>> {
+        self.get_setting(key).await?
+            .map(|value| value.trim().parse().map_err(|e| anyhow!("Setting '{}' is not a number: {}", key, e)))
+            .transpose()
+    }
+
+    // ==================== Secrets ====================
+
+    /// Store a credential encrypted at rest (OS keychain key, or a machine-bound fallback)
+    pub async fn set_secret(&self, key: &str, value: &str) -> Result<()> {
+        let (key, value) = (key.to_string(), value.to_string());
+        self.with_db(move |conn, plugin_id| secrets::set_secret(conn, secrets::keys(), plugin_id, &key, &value)).await
+    }
+
+    /// Decrypt one of this plugin's secrets, `None` if it was never set
+    pub async fn get_secret(&self, key: &str) -> Result<Option<String>> {
+        let key = key.to_string();
+        self.with_db(move |conn, plugin_id| secrets::get_secret(conn, secrets::keys(), plugin_id, &key)).await
+    }
+
+    /// Remove one of this plugin's secrets
+    pub async fn delete_secret(&self, key: &str) -> Result<bool> {
+        let key = key.to_string();
+        self.with_db(move |conn, plugin_id| secrets::delete_secret(conn, plugin_id, &key)).await
+    }
+
+    /// Path of the database file backing `db()`
+    pub fn db_path(&self) -> &str {
+        &self.db_path
+    }
+
+    // ==================== Config ====================
+
+    /// Deserialize the plugin's config into a typed struct (a missing config reads as `{}`)
+    pub fn config<T: DeserializeOwned>(&self) -> Result<T> {
+        let raw = match &self.config {
+            Value::Null => Value::Object(Default::default()),
+            other => other.clone(),
+        };
+
+        serde_path_to_error::deserialize(raw).map_err(|e| {
+            anyhow!("Invalid config for plugin '{}' at '{}': {}", self.plugin_id, e.path(), e.inner())
+        })
+    }
+
+    // ==================== Scheduling ====================
+
+    /// Emit `topic` with `payload` on a schedule (`every 1h` or five-field UTC cron), persisted across restarts
+    /// Re-scheduling the same spec and topic updates the payload. Returns the schedule id.
+    pub async fn schedule<T: Serialize>(&self, spec: &str, topic: &str, payload: &T) -> Result<i64> {
+        let payload = serde_json::to_value(payload)?;
+        let (spec, topic) = (spec.to_string(), topic.to_string());
+        self.with_db(move |conn, plugin_id| {
+            scheduler::upsert_schedule(conn, plugin_id, &spec, &topic, &payload, chrono::Utc::now())
+        }).await
+    }
+
+    /// Remove one of this plugin's schedules
+    pub async fn unschedule(&self, schedule_id: i64) -> Result<bool> {
+        self.with_db(move |conn, plugin_id| scheduler::remove_schedule(conn, plugin_id, schedule_id)).await
+    }
+
+    /// This plugin's schedules
+    pub async fn schedules(&self) -> Result<Vec<scheduler::ScheduledEvent>> {
+        self.with_db(|conn, plugin_id| scheduler::list_schedules(conn, plugin_id)).await
+    }
+
+    // ==================== Outbound HTTP ====================
+
+    /// Shared client for calling external APIs (one pool for every plugin)
+    pub fn http(&self) -> &'static HttpClient {
+        http_client::shared()
+    }
+
+    /// Take a token from this plugin's `key` bucket before calling a third-party API
+    /// `rate_limits.<key>` in the plugin config overrides `per_minute` and the wait/error policy.
+    pub async fn rate_limit(&self, key: &str, per_minute: u32) -> Result<()> {
+        let limit = match self.config.get("rate_limits").and_then(|limits| limits.get(key)) {
+            Some(configured) => RateLimitConfig::deserialize(configured).map_err(|e| {
+                anyhow!("Invalid config for plugin '{}' at 'rate_limits.{}': {}", self.plugin_id, key, e)
+            })?,
+            None => RateLimitConfig { per_minute, policy: LimitPolicy::Wait },
+        };
+        rate_limit::shared().acquire(&format!("{}:{}", self.plugin_id, key), limit).await
+    }
+
+    // ==================== OAuth ====================
+
+    /// OAuth2 authorization-code helper for this plugin's integration
+    pub fn oauth(&self, config: OAuthConfig) -> Arc<OAuthClient> {
+        Arc::new(OAuthClient::new(&self.plugin_id, config, &self.db_path))
+    }
+
     // ==================== Events ====================
 
     /// Publish event
@@ -45,16 +240,97 @@ impl PluginContext {
         self.event_bus.publish_typed(&self.plugin_id, event_type, payload);
     }
 
+    /// Emit a request event and wait for a reply from whoever handles it
+    pub async fn request<T: Serialize>(&self, event_type: &str, payload: &T, timeout: Duration) -> Result<Value> {
+        self.event_bus.request(&self.plugin_id, event_type, payload, timeout).await
+    }
+
+    /// Reply to a request event received via `subscribe_to`
+    pub fn reply<T: Serialize>(&self, request: &Event, payload: &T) -> Result<()> {
+        self.event_bus.reply(&self.plugin_id, request, payload)
+    }
+
     /// Subscribe to specific event type
     pub async fn subscribe_to(&self, event_type: &str) -> broadcast::Receiver<Event> {
         self.event_bus.subscribe_to(event_type).await
     }
 
+    /// Subscribe to an event type, also returning up to `replay` events it missed
+    pub async fn subscribe_to_with_replay(&self, event_type: &str, replay: usize) -> (Vec<Event>, broadcast::Receiver<Event>) {
+        self.event_bus.subscribe_to_with_replay(event_type, replay).await
+    }
+
+    /// Recent events whose type starts with `topic_prefix`, oldest first
+    pub fn recent_events(&self, topic_prefix: &str, limit: usize) -> Vec<Event> {
+        self.event_bus.recent(topic_prefix, limit)
+    }
+
     /// Subscribe to all events
     pub fn subscribe_all(&self) -> broadcast::Receiver<Event> {
         self.event_bus.subscribe()
     }
 
+    /// Handle events on `topic` with retries; events that still fail are dead-lettered
+    /// Abort the returned handle in `stop`.
+    pub async fn on_event<F, Fut>(&self, topic: &str, retry: HandlerRetry, handler: F) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let handler: EventHandler = Arc::new(move |event: Event| -> BoxFuture<'static, Result<()>> { Box::pin(handler(event)) });
+
+        let plugin_id = self.plugin_id.clone();
+        let subscribed_topic = topic.to_string();
+        let db_path = self.db_path.clone();
+        let on_failure: FailureSink = Arc::new(move |event: &Event, error: &str, attempts: u32| {
+            let recorded = database::get_pool(&db_path)
+                .and_then(|pool| Ok(pool.get()?))
+                .and_then(|conn| dead_letters::record_dead_letter(&conn, &plugin_id, &subscribed_topic, event, error, attempts));
+            match recorded {
+                Ok(id) => log::warn!("📮 [{}] Dead-lettered '{}' event (#{})", plugin_id, event.event_type, id),
+                Err(e) => log::error!("❌ [{}] Failed to dead-letter '{}' event: {}", plugin_id, event.event_type, e),
+            }
+        });
+
+        self.event_bus.subscribe_handler(&self.plugin_id, topic, retry, handler, on_failure).await
+    }
+
+    /// This plugin's dead-lettered events, oldest first, optionally for one topic
+    pub async fn dead_letters(&self, topic: Option<&str>) -> Result<Vec<DeadLetter>> {
+        let topic = topic.map(str::to_string);
+        self.with_db(move |conn, plugin_id| dead_letters::list_dead_letters(conn, plugin_id, topic.as_deref())).await
+    }
+
+    /// Run this plugin's dead letters for `topic` through its `on_event` handler again
+    /// Returns how many succeeded; the rest keep their new error and attempt count.
+    pub async fn replay_dead_letters(&self, topic: &str) -> Result<usize> {
+        let (handler, retry) = self.event_bus.handler(&self.plugin_id, topic)
+            .ok_or_else(|| anyhow!("Plugin '{}' has no handler for '{}'; call on_event first", self.plugin_id, topic))?;
+
+        let mut replayed = 0;
+        for letter in self.dead_letters(Some(topic)).await? {
+            let letter_id = letter.id;
+            match events::handle_with_retry(&handler, &letter.event, retry).await {
+                Ok(()) => {
+                    self.with_db(move |conn, plugin_id| dead_letters::delete_dead_letter(conn, plugin_id, letter_id)).await?;
+                    replayed += 1;
+                }
+                Err((error, attempts)) => {
+                    self.with_db(move |conn, _| dead_letters::update_dead_letter(conn, letter_id, &error, attempts)).await?;
+                }
+            }
+        }
+
+        log::info!("📮 [{}] Replayed {} dead-lettered '{}' events", self.plugin_id, replayed, topic);
+        Ok(replayed)
+    }
+
+    /// Signal that flips to `true` when the bridge shuts down
+    /// Background loops started in `start` should exit when it changes.
+    pub fn shutdown_signal(&self) -> tokio::sync::watch::Receiver<bool> {
+        crate::bridge::shutdown_signal()
+    }
+
     // ==================== Services ====================
 
     /// Register a service method that other plugins can call
@@ -73,6 +349,14 @@ impl PluginContext {
         self.service_registry.call(&service_id, input).await
     }
 
+    /// Call several services concurrently, one result per call in order
+    pub async fn call_services_batch(&self, calls: Vec<(&str, &str, Value)>) -> Vec<Result<Value>> {
+        let calls = calls.into_iter()
+            .map(|(plugin_id, method, input)| (format!("{}.{}", plugin_id, method), input))
+            .collect();
+        self.service_registry.call_batch(calls).await
+    }
+
     /// Check if a service exists
     pub async fn has_service(&self, plugin_id: &str, method: &str) -> bool {
         let service_id = format!("{}.{}", plugin_id, method);
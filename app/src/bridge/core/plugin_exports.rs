@@ -3,13 +3,22 @@
 
 use crate::bridge::core::plugin_context::PluginContext;
 use std::collections::HashMap;
-use std::sync::{Mutex, Arc};
+use std::sync::{Mutex, RwLock, Arc};
+use std::time::SystemTime;
 use once_cell::sync::Lazy;
 use libloading::Library;
 use tokio::runtime::Runtime;
 
+/// Major version of the plugin API this host implements
+/// DLLs whose `plugin_api_version()` export reports a different major are refused
+pub const PLUGIN_API_MAJOR: u64 = 1;
+
 // Global registry to track plugin_id -> Library mapping
-pub static PLUGIN_LIBRARIES: Lazy<Mutex<HashMap<String, Arc<Library>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Read on every plugin request, so lookups take a shared lock and clone the Arc out
+pub static PLUGIN_LIBRARIES: Lazy<RwLock<HashMap<String, Arc<Library>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+// Modification time of the DLL each loaded library was built from (for hot reload)
+pub static PLUGIN_LIBRARY_MTIMES: Lazy<Mutex<HashMap<String, SystemTime>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
 // Global registry for embedded JS content (locked-plugins mode)
 #[cfg(feature = "locked-plugins")]
@@ -18,6 +27,9 @@ pub static EMBEDDED_JS: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mute
 // Global router registry for dynamic plugin route registration
 pub static GLOBAL_ROUTER_REGISTRY: Lazy<Mutex<Option<crate::bridge::core::plugin_router::RouterRegistry>>> = Lazy::new(|| Mutex::new(None));
 
+// Global service registry for service introspection
+pub static GLOBAL_SERVICE_REGISTRY: Lazy<Mutex<Option<Arc<crate::bridge::core::services::ServiceRegistry>>>> = Lazy::new(|| Mutex::new(None));
+
 // Shared tokio runtime for all DLL plugins
 pub static SHARED_RUNTIME: Lazy<Arc<Runtime>> = Lazy::new(|| {
     Arc::new(
@@ -31,19 +43,21 @@ pub static SHARED_RUNTIME: Lazy<Arc<Runtime>> = Lazy::new(|| {
 });
 
 /// Get pointer to the shared runtime for passing to DLL handlers
+/// Points at the `Runtime` inside `SHARED_RUNTIME`, which lives for the whole process
 pub fn get_shared_runtime_ptr() -> *const () {
     Arc::as_ptr(&SHARED_RUNTIME) as *const ()
 }
 
 /// Register a plugin library for handler lookups
 pub fn register_plugin_library(plugin_id: String, library: Arc<Library>) {
-    let mut libs = PLUGIN_LIBRARIES.lock().unwrap();
+    let mut libs = PLUGIN_LIBRARIES.write().unwrap();
     libs.insert(plugin_id, library);
 }
 
 /// Unload a plugin library (removes from registry and drops the library handle)
 pub fn unload_plugin_library(plugin_id: &str) -> bool {
-    let mut libs = PLUGIN_LIBRARIES.lock().unwrap();
+    PLUGIN_LIBRARY_MTIMES.lock().unwrap().remove(plugin_id);
+    let mut libs = PLUGIN_LIBRARIES.write().unwrap();
     if libs.remove(plugin_id).is_some() {
         log::info!("[FFI] Unloaded plugin library: {}", plugin_id);
         true
@@ -54,10 +68,22 @@ pub fn unload_plugin_library(plugin_id: &str) -> bool {
 
 /// Get a reference to a plugin library by ID
 pub fn get_plugin_library(plugin_id: &str) -> Option<Arc<Library>> {
-    let libs = PLUGIN_LIBRARIES.lock().unwrap();
+    let libs = PLUGIN_LIBRARIES.read().unwrap();
     libs.get(plugin_id).cloned()
 }
 
+/// Record the source DLL modification time for a loaded plugin library
+pub fn record_library_mtime(plugin_id: &str, mtime: SystemTime) {
+    let mut mtimes = PLUGIN_LIBRARY_MTIMES.lock().unwrap();
+    mtimes.insert(plugin_id.to_string(), mtime);
+}
+
+/// Get the source DLL modification time of a loaded plugin library
+pub fn get_library_mtime(plugin_id: &str) -> Option<SystemTime> {
+    let mtimes = PLUGIN_LIBRARY_MTIMES.lock().unwrap();
+    mtimes.get(plugin_id).copied()
+}
+
 /// Register embedded JS content (locked-plugins mode)
 #[cfg(feature = "locked-plugins")]
 pub fn register_embedded_js(plugin_id: String, content: String) {
@@ -84,6 +110,18 @@ pub fn get_global_router_registry() -> Option<crate::bridge::core::plugin_router
     global.clone()
 }
 
+/// Set the global service registry (called during bridge startup)
+pub fn set_global_service_registry(registry: Arc<crate::bridge::core::services::ServiceRegistry>) {
+    let mut global = GLOBAL_SERVICE_REGISTRY.lock().unwrap();
+    *global = Some(registry);
+}
+
+/// Get the global service registry
+pub fn get_global_service_registry() -> Option<Arc<crate::bridge::core::services::ServiceRegistry>> {
+    let global = GLOBAL_SERVICE_REGISTRY.lock().unwrap();
+    global.clone()
+}
+
 /// Emit an event
 #[no_mangle]
 pub extern "C" fn webarcade_emit_event(
@@ -34,6 +34,11 @@ impl PluginManager {
     }
 
     pub fn register<P: Plugin + 'static>(&mut self, plugin: P) {
+        self.register_with_config(plugin, serde_json::Value::Null);
+    }
+
+    /// Register a plugin along with its config blob, readable via `ctx.config()`
+    pub fn register_with_config<P: Plugin + 'static>(&mut self, plugin: P, config: serde_json::Value) {
         let metadata = plugin.metadata();
         let plugin_id = metadata.id.clone();
 
@@ -43,7 +48,7 @@ impl PluginManager {
             self.service_registry.clone(),
             self.router_registry.clone_registry(),
             self.db_path.clone(),
-        ));
+        ).with_config(config));
 
         self.contexts.insert(plugin_id.clone(), ctx);
         self.plugins.insert(plugin_id, Box::new(plugin));
@@ -83,16 +88,51 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Restart one plugin without touching the others
+    ///
+    /// Stops it, drops its routes and services, then runs `init` and `start`
+    /// again so it re-registers them with the current config.
+    pub async fn reload(&self, plugin_id: &str) -> Result<()> {
+        let (plugin, ctx) = match (self.plugins.get(plugin_id), self.contexts.get(plugin_id)) {
+            (Some(plugin), Some(ctx)) => (plugin, ctx),
+            _ => return Err(anyhow!("Plugin '{}' is not registered", plugin_id)),
+        };
+
+        plugin.stop().await.map_err(|e| {
+            anyhow!("Failed to stop plugin '{}': {}", plugin_id, e)
+        })?;
+
+        self.router_registry.unregister(plugin_id).await;
+        self.service_registry.unregister_plugin(plugin_id).await;
+
+        plugin.init(ctx).await.map_err(|e| {
+            anyhow!("Failed to initialize plugin '{}': {}", plugin_id, e)
+        })?;
+        plugin.start(ctx.clone()).await.map_err(|e| {
+            anyhow!("Failed to start plugin '{}': {}", plugin_id, e)
+        })?;
+
+        log::info!("🔄 Reloaded plugin {}", plugin_id);
+        Ok(())
+    }
+
     pub fn list_plugins(&self) -> Vec<PluginMetadata> {
         self.plugins.values().map(|p| p.metadata()).collect()
     }
 
+    /// Order plugins so every plugin comes after the ones it depends on
+    ///
+    /// Ids are visited alphabetically so the order doesn't depend on
+    /// registration order or HashMap iteration.
     fn resolve_dependencies(&self) -> Result<Vec<String>> {
         let mut order = Vec::new();
         let mut visited = HashSet::new();
-        let mut visiting = HashSet::new();
+        let mut visiting = Vec::new();
+
+        let mut plugin_ids: Vec<&String> = self.plugins.keys().collect();
+        plugin_ids.sort();
 
-        for plugin_id in self.plugins.keys() {
+        for plugin_id in plugin_ids {
             if !visited.contains(plugin_id) {
                 self.visit_plugin(plugin_id, &mut order, &mut visited, &mut visiting)?;
             }
@@ -106,20 +146,24 @@ impl PluginManager {
         plugin_id: &str,
         order: &mut Vec<String>,
         visited: &mut HashSet<String>,
-        visiting: &mut HashSet<String>,
+        visiting: &mut Vec<String>,
     ) -> Result<()> {
         if visited.contains(plugin_id) {
             return Ok(());
         }
 
-        if visiting.contains(plugin_id) {
-            return Err(anyhow!("Circular dependency detected: {}", plugin_id));
+        if let Some(start) = visiting.iter().position(|id| id == plugin_id) {
+            let mut cycle = visiting[start..].to_vec();
+            cycle.push(plugin_id.to_string());
+            return Err(anyhow!("Circular plugin dependency: {}", cycle.join(" -> ")));
         }
 
-        visiting.insert(plugin_id.to_string());
+        visiting.push(plugin_id.to_string());
 
         if let Some(plugin) = self.plugins.get(plugin_id) {
-            for dep in &plugin.metadata().dependencies {
+            let mut dependencies = plugin.metadata().dependencies;
+            dependencies.sort();
+            for dep in &dependencies {
                 if !self.plugins.contains_key(dep) {
                     return Err(anyhow!("Plugin '{}' depends on unregistered '{}'", plugin_id, dep));
                 }
@@ -127,10 +171,179 @@ impl PluginManager {
             }
         }
 
-        visiting.remove(plugin_id);
+        visiting.pop();
         visited.insert(plugin_id.to_string());
         order.push(plugin_id.to_string());
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use async_trait::async_trait;
+
+    struct RecordingPlugin {
+        id: &'static str,
+        dependencies: Vec<&'static str>,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RecordingPlugin {
+        fn new(id: &'static str, calls: &Arc<Mutex<Vec<String>>>) -> Self {
+            Self { id, dependencies: vec![], calls: calls.clone() }
+        }
+
+        fn depends_on(mut self, dependencies: &[&'static str]) -> Self {
+            self.dependencies = dependencies.to_vec();
+            self
+        }
+    }
+
+    #[async_trait]
+    impl Plugin for RecordingPlugin {
+        fn metadata(&self) -> PluginMetadata {
+            PluginMetadata {
+                id: self.id.to_string(),
+                name: self.id.to_string(),
+                version: "1.0.0".to_string(),
+                description: String::new(),
+                author: String::new(),
+                dependencies: self.dependencies.iter().map(|d| d.to_string()).collect(),
+            }
+        }
+
+        async fn init(&self, ctx: &PluginContext) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("{}.init", self.id));
+            ctx.provide_service("ping", |_| async move { Ok(serde_json::Value::Null) }).await;
+            Ok(())
+        }
+
+        async fn start(&self, _ctx: Arc<PluginContext>) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("{}.start", self.id));
+            Ok(())
+        }
+
+        async fn stop(&self) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("{}.stop", self.id));
+            Ok(())
+        }
+    }
+
+    fn manager() -> (PluginManager, Arc<ServiceRegistry>) {
+        let services = Arc::new(ServiceRegistry::new());
+        let manager = PluginManager::new(
+            Arc::new(EventBus::new()),
+            services.clone(),
+            RouterRegistry::new(),
+            ":memory:".to_string(),
+        );
+        (manager, services)
+    }
+
+    #[tokio::test]
+    async fn test_reload_restarts_only_that_plugin() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (mut manager, services) = manager();
+        manager.register(RecordingPlugin::new("currency", &calls));
+        manager.register(RecordingPlugin::new("packs", &calls));
+        manager.init_all().await.unwrap();
+        manager.start_all().await.unwrap();
+        calls.lock().unwrap().clear();
+
+        manager.reload("packs").await.unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), vec!["packs.stop", "packs.init", "packs.start"]);
+        assert!(services.has_service("packs.ping").await);
+        assert!(services.has_service("currency.ping").await);
+    }
+
+    #[tokio::test]
+    async fn test_init_follows_dependencies_not_registration_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (mut manager, _) = manager();
+        manager.register(RecordingPlugin::new("packs", &calls).depends_on(&["currency", "inventory"]));
+        manager.register(RecordingPlugin::new("inventory", &calls).depends_on(&["currency"]));
+        manager.register(RecordingPlugin::new("currency", &calls));
+
+        manager.init_all().await.unwrap();
+        manager.start_all().await.unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), vec![
+            "currency.init", "inventory.init", "packs.init",
+            "currency.start", "inventory.start", "packs.start",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_cyclic_dependencies_fail() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (mut manager, _) = manager();
+        manager.register(RecordingPlugin::new("packs", &calls).depends_on(&["currency"]));
+        let err = manager.init_all().await.unwrap_err();
+        assert!(err.to_string().contains("depends on unregistered 'currency'"));
+
+        manager.register(RecordingPlugin::new("currency", &calls).depends_on(&["packs"]));
+        let err = manager.init_all().await.unwrap_err();
+        assert!(err.to_string().contains("currency -> packs -> currency"));
+        assert!(calls.lock().unwrap().is_empty());
+    }
+
+    #[derive(serde::Deserialize)]
+    struct LevelsConfig {
+        #[serde(default = "default_xp_per_message")]
+        xp_per_message: i64,
+    }
+
+    fn default_xp_per_message() -> i64 { 10 }
+
+    struct ConfiguredPlugin {
+        xp_per_message: Arc<Mutex<Option<i64>>>,
+    }
+
+    #[async_trait]
+    impl Plugin for ConfiguredPlugin {
+        fn metadata(&self) -> PluginMetadata {
+            PluginMetadata {
+                id: "levels".to_string(),
+                name: "Levels".to_string(),
+                version: "1.0.0".to_string(),
+                description: String::new(),
+                author: String::new(),
+                dependencies: vec![],
+            }
+        }
+
+        async fn init(&self, ctx: &PluginContext) -> Result<()> {
+            let config: LevelsConfig = ctx.config()?;
+            *self.xp_per_message.lock().unwrap() = Some(config.xp_per_message);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typed_config_uses_defaults_and_reports_bad_fields() {
+        let xp = Arc::new(Mutex::new(None));
+        let (mut defaults, _) = manager();
+        defaults.register(ConfiguredPlugin { xp_per_message: xp.clone() });
+        defaults.init_all().await.unwrap();
+        assert_eq!(*xp.lock().unwrap(), Some(10));
+
+        let (mut malformed, _) = manager();
+        malformed.register_with_config(
+            ConfiguredPlugin { xp_per_message: xp.clone() },
+            serde_json::json!({ "xp_per_message": "lots" }),
+        );
+        let err = malformed.init_all().await.unwrap_err().to_string();
+        assert!(err.contains("levels"), "{}", err);
+        assert!(err.contains("xp_per_message"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_reload_unknown_plugin_fails() {
+        let (manager, _) = manager();
+        assert!(manager.reload("missing").await.is_err());
+    }
+}
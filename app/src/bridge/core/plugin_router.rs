@@ -2,25 +2,64 @@ use hyper::{Method, Request, Response, StatusCode, body::Incoming};
 use http_body_util::{combinators::BoxBody, Full, BodyExt};
 use hyper::body::Bytes;
 use std::convert::Infallible;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use serde::Serialize;
 
 pub type RouteHandler = Box<dyn Fn(String, String, Request<Incoming>) -> BoxFuture<Response<BoxBody<Bytes, Infallible>>> + Send + Sync>;
 pub type BoxFuture<T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send>>;
 
+/// Middleware runs before a matched route's handler. Returning Some(response)
+/// short-circuits the request (e.g. 401/429), None passes it on.
+pub type Middleware = Arc<dyn Fn(&Request<Incoming>) -> Option<Response<BoxBody<Bytes, Infallible>>> + Send + Sync>;
+
+/// Description of a registered route (for introspection)
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RouteInfo {
+    pub path: String,
+    pub method: String,
+    /// Name of the plugin handler serving this route, if known
+    pub handler: Option<String>,
+}
+
 /// A router for a specific plugin
 pub struct PluginRouter {
     routes: HashMap<(Method, String), RouteHandler>,
+    handler_names: HashMap<(Method, String), String>,
+    middleware: Vec<Middleware>,
+    /// Match paths exactly as sent instead of running them through `normalize_path`
+    strict_paths: bool,
 }
 
 impl PluginRouter {
     pub fn new() -> Self {
         Self {
             routes: HashMap::new(),
+            handler_names: HashMap::new(),
+            middleware: Vec::new(),
+            strict_paths: false,
         }
     }
 
+    /// Treat `/hello/` and `/hello` (and `//`) as different paths
+    ///
+    /// By default incoming paths are normalized first, so trailing slashes and
+    /// empty segments don't affect which route matches.
+    pub fn with_strict_paths(mut self) -> Self {
+        self.strict_paths = true;
+        self
+    }
+
+    /// Add middleware that runs, in registration order, before every route handler
+    pub fn with_middleware<F>(mut self, middleware: F) -> Self
+    where
+        F: Fn(&Request<Incoming>) -> Option<Response<BoxBody<Bytes, Infallible>>> + Send + Sync + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
     /// Register a route handler
     pub fn route<F>(&mut self, method: Method, path: &str, handler: F)
     where
@@ -29,6 +68,28 @@ impl PluginRouter {
         self.routes.insert((method, path.to_string()), Box::new(handler));
     }
 
+    /// Register a route handler and record the name of the handler serving it
+    pub fn route_named<F>(&mut self, method: Method, path: &str, handler_name: &str, handler: F)
+    where
+        F: Fn(String, String, Request<Incoming>) -> BoxFuture<Response<BoxBody<Bytes, Infallible>>> + Send + Sync + 'static,
+    {
+        self.handler_names.insert((method.clone(), path.to_string()), handler_name.to_string());
+        self.route(method, path, handler);
+    }
+
+    /// List registered routes, sorted by path then method
+    pub fn routes(&self) -> Vec<RouteInfo> {
+        let mut routes: Vec<RouteInfo> = self.routes.keys()
+            .map(|key| RouteInfo {
+                path: key.1.clone(),
+                method: key.0.to_string(),
+                handler: self.handler_names.get(key).cloned(),
+            })
+            .collect();
+        routes.sort();
+        routes
+    }
+
     /// Handle a request for this plugin
     pub async fn handle(
         &self,
@@ -42,61 +103,142 @@ impl PluginRouter {
             return Some(cors_preflight_response());
         }
 
+        let normalized;
+        let path = if self.strict_paths {
+            path
+        } else {
+            normalized = normalize_path(path);
+            normalized.as_str()
+        };
+
+        let Some(handler) = self.find_handler(method, path) else {
+            // Path exists under another method: 405 rather than 404
+            let allowed = self.allowed_methods(path);
+            if allowed.is_empty() {
+                return None;
+            }
+            return Some(method_not_allowed_response(&allowed));
+        };
+
+        // Middleware only runs for matched routes, so unknown paths still 404
+        for middleware in &self.middleware {
+            if let Some(response) = middleware(&req) {
+                return Some(response);
+            }
+        }
+
+        Some(handler(path.to_string(), query.to_string(), req).await)
+    }
+
+    /// Methods registered for any route pattern matching `path`, sorted
+    fn allowed_methods(&self, path: &str) -> Vec<Method> {
+        let mut methods: Vec<Method> = self.routes.keys()
+            .filter(|(_, route_path)| route_path == path || match_route(route_path, path).is_some())
+            .map(|(method, _)| method.clone())
+            .collect();
+        methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        methods.dedup();
+        methods
+    }
+
+    /// Find the handler for a request
+    fn find_handler(&self, method: &Method, path: &str) -> Option<&RouteHandler> {
         // Try exact match first
         if let Some(handler) = self.routes.get(&(method.clone(), path.to_string())) {
-            return Some(handler(path.to_string(), query.to_string(), req).await);
+            return Some(handler);
         }
 
-        // Try pattern matching for dynamic routes
-        for ((route_method, route_path), handler) in &self.routes {
-            if route_method == method {
-                // Check if it's a wildcard match
-                if route_path.ends_with("/*") {
-                    let prefix = &route_path[..route_path.len() - 2];
-                    if path.starts_with(prefix) {
-                        return Some(handler(path.to_string(), query.to_string(), req).await);
-                    }
-                }
-                // Check if it's a parameter match (e.g., "/user/:id")
-                if route_path.contains("/:") {
-                    if paths_match(route_path, path) {
-                        return Some(handler(path.to_string(), query.to_string(), req).await);
-                    }
-                }
-            }
-        }
+        // Then dynamic routes: `:param` routes win over wildcards, and among
+        // wildcards the most specific (longest) pattern wins
+        self.routes.iter()
+            .filter(|((route_method, route_path), _)| route_method == method && match_route(route_path, path).is_some())
+            .min_by_key(|((_, route_path), _)| (route_rank(route_path), std::cmp::Reverse(route_path.split('/').count()), route_path.clone()))
+            .map(|(_, handler)| handler)
+    }
+}
 
-        None
+/// Precedence of a route pattern: exact (0), `:param` (1), trailing wildcard (2)
+fn route_rank(pattern: &str) -> u8 {
+    if pattern.split('/').any(|part| part.starts_with('*')) {
+        2
+    } else if pattern.split('/').any(|part| part.starts_with(':')) {
+        1
+    } else {
+        0
     }
 }
 
-/// Check if a path matches a route pattern
-fn paths_match(pattern: &str, path: &str) -> bool {
+/// Canonical form of a request path: empty segments (trailing or doubled
+/// slashes) are dropped, so `/hello/`, `//hello` and `/hello` are the same route
+pub fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Match a path against a route pattern and extract its parameters
+///
+/// - `:name` matches exactly one segment
+/// - a trailing `*name` matches the rest of the path (zero or more segments) as one param
+/// - a trailing bare `*` matches the rest of the path without capturing it
+///
+/// Path segments are percent-decoded before comparing, so `/user/%20bob`
+/// gives `id = " bob"`. An encoded `%2F` stays inside its segment.
+pub fn match_route(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
     let pattern_parts: Vec<&str> = pattern.split('/').collect();
-    let path_parts: Vec<&str> = path.split('/').collect();
+    let path_parts: Vec<String> = path.split('/')
+        .map(|part| urlencoding::decode(part).map(|p| p.into_owned()).unwrap_or_else(|_| part.to_string()))
+        .collect();
+    let mut params = HashMap::new();
 
-    if pattern_parts.len() != path_parts.len() {
-        return false;
-    }
+    for (i, pattern_part) in pattern_parts.iter().enumerate() {
+        if let Some(name) = pattern_part.strip_prefix('*') {
+            // Wildcards are only allowed as the last segment
+            if i != pattern_parts.len() - 1 {
+                return None;
+            }
+            let rest = path_parts.get(i..).map(|parts| parts.join("/")).unwrap_or_default();
+            if !name.is_empty() {
+                params.insert(name.to_string(), rest);
+            }
+            return Some(params);
+        }
 
-    for (pattern_part, path_part) in pattern_parts.iter().zip(path_parts.iter()) {
-        if pattern_part.starts_with(':') {
+        let path_part = path_parts.get(i)?;
+        if let Some(name) = pattern_part.strip_prefix(':') {
             // This is a parameter, matches anything
-            continue;
-        }
-        if pattern_part != path_part {
-            return false;
+            params.insert(name.to_string(), path_part.clone());
+        } else if *pattern_part != path_part.as_str() {
+            return None;
         }
     }
 
-    true
+    if pattern_parts.len() == path_parts.len() {
+        Some(params)
+    } else {
+        None
+    }
+}
+
+/// 405 Method Not Allowed with an `Allow` header listing `allowed`
+fn method_not_allowed_response(allowed: &[Method]) -> Response<BoxBody<Bytes, Infallible>> {
+    let allow = allowed.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", ");
+    let json = serde_json::json!({
+        "error": "Method not allowed",
+        "allowed": allowed.iter().map(|m| m.as_str()).collect::<Vec<_>>()
+    }).to_string();
+
+    Response::builder()
+        .status(StatusCode::METHOD_NOT_ALLOWED)
+        .header("Allow", allow)
+        .header("Content-Type", "application/json")
+        .body(BoxBody::new(Full::new(Bytes::from(json)).map_err(|err: Infallible| match err {})))
+        .unwrap()
 }
 
 /// Create a CORS preflight response
 fn cors_preflight_response() -> Response<BoxBody<Bytes, Infallible>> {
     Response::builder()
         .status(StatusCode::OK)
-        .header("Access-Control-Allow-Origin", "*")
         .header("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS")
         .header("Access-Control-Allow-Headers", "Content-Type, Authorization")
         .header("Access-Control-Max-Age", "86400")
@@ -123,6 +265,17 @@ impl RouterRegistry {
         routers.insert(plugin_name, router);
     }
 
+    /// Remove a plugin's router. Returns true if one was registered.
+    pub async fn unregister(&self, plugin_name: &str) -> bool {
+        let mut routers = self.routers.write().await;
+        routers.remove(plugin_name).is_some()
+    }
+
+    /// Whether a router is registered for this plugin
+    pub async fn has_plugin(&self, plugin_name: &str) -> bool {
+        self.routers.read().await.contains_key(plugin_name)
+    }
+
     /// Route a request to the appropriate plugin router
     pub async fn route(
         &self,
@@ -140,9 +293,191 @@ impl RouterRegistry {
         }
     }
 
+    /// List the routes of every registered plugin, keyed by plugin name
+    pub async fn list_routes(&self) -> BTreeMap<String, Vec<RouteInfo>> {
+        let routers = self.routers.read().await;
+        routers.iter()
+            .map(|(plugin_name, router)| (plugin_name.clone(), router.routes()))
+            .collect()
+    }
+
     pub fn clone_registry(&self) -> Self {
         Self {
             routers: Arc::clone(&self.routers),
         }
     }
 }
+
+/// Serve a registry's routes for `plugin_name` on a local port, so tests can
+/// drive `PluginRouter::handle` (middleware included) with real requests
+#[cfg(test)]
+pub(crate) async fn serve_for_test(registry: RouterRegistry, plugin_name: &str) -> String {
+    use hyper::server::conn::http1;
+    use hyper::service::service_fn;
+    use hyper_util::rt::TokioIo;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let plugin_name = plugin_name.to_string();
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer_addr) = listener.accept().await.unwrap();
+            let registry = registry.clone();
+            let plugin_name = plugin_name.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |mut req: Request<Incoming>| {
+                    req.extensions_mut().insert(peer_addr);
+                    let registry = registry.clone();
+                    let plugin_name = plugin_name.clone();
+                    async move {
+                        let method = req.method().clone();
+                        let path = req.uri().path().to_string();
+                        let query = req.uri().query().unwrap_or("").to_string();
+                        let response = registry.route(&plugin_name, &method, &path, &query, req).await
+                            .unwrap_or_else(|| Response::builder().status(StatusCode::NOT_FOUND).body(BoxBody::new(Full::new(Bytes::new()).map_err(|err: Infallible| match err {}))).unwrap());
+                        Ok::<_, Infallible>(response)
+                    }
+                });
+                let _ = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await;
+            });
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::bridge::core::router_utils::{bearer_token_auth, rate_limit_requests};
+
+    /// Router with one GET /secret route that counts its calls
+    fn counting_router(router: PluginRouter, calls: Arc<AtomicUsize>) -> PluginRouter {
+        let mut router = router;
+        router.route(Method::GET, "/secret", move |_path, _query, _req| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { cors_preflight_response() })
+        });
+        router
+    }
+
+    async fn serve(router: PluginRouter) -> String {
+        let registry = RouterRegistry::new();
+        registry.register("demo".to_string(), router).await;
+        serve_for_test(registry, "demo").await
+    }
+
+    fn noop_handler(_path: String, _query: String, _req: Request<Incoming>) -> BoxFuture<Response<BoxBody<Bytes, Infallible>>> {
+        Box::pin(async { cors_preflight_response() })
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> Option<HashMap<String, String>> {
+        Some(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+    }
+
+    #[test]
+    fn test_match_route_params_and_wildcards() {
+        assert_eq!(match_route("/hello", "/hello"), params(&[]));
+        assert_eq!(match_route("/user/:id", "/user/42"), params(&[("id", "42")]));
+        assert_eq!(match_route("/user/:id", "/user/42/posts"), None);
+        assert_eq!(match_route("/files/*path", "/files/a/b/c.txt"), params(&[("path", "a/b/c.txt")]));
+        assert_eq!(match_route("/files/*path", "/files"), params(&[("path", "")]));
+        assert_eq!(match_route("/files/:bucket/*rest", "/files/img/x/y.png"), params(&[("bucket", "img"), ("rest", "x/y.png")]));
+        assert_eq!(match_route("/static/*", "/static/app.js"), params(&[]));
+        assert_eq!(match_route("/files/*path", "/other/a"), None);
+        assert_eq!(match_route("/*path/edit", "/a/edit"), None);
+    }
+
+    #[test]
+    fn test_encoded_segments_and_slashes() {
+        assert_eq!(match_route("/user/:id", "/user/%20bob"), params(&[("id", " bob")]));
+        assert_eq!(match_route("/user/:id", "/user/a%2Fb"), params(&[("id", "a/b")]));
+        assert_eq!(match_route("/hello world", "/hello%20world"), params(&[]));
+
+        assert_eq!(normalize_path("/hello/"), "/hello");
+        assert_eq!(normalize_path("//hello//there/"), "/hello/there");
+        assert_eq!(normalize_path("/"), "/");
+        assert_eq!(normalize_path(""), "/");
+        assert_eq!(match_route("/user/:id", &normalize_path("/user//42/")), params(&[("id", "42")]));
+    }
+
+    #[test]
+    fn test_route_rank_prefers_exact_then_params_then_wildcard() {
+        assert!(route_rank("/files/readme") < route_rank("/files/:name"));
+        assert!(route_rank("/files/:name") < route_rank("/files/*path"));
+    }
+
+    #[test]
+    fn test_allowed_methods_for_wrong_method() {
+        let mut router = PluginRouter::new();
+        router.route(Method::GET, "/users/:id", noop_handler);
+        router.route(Method::DELETE, "/users/:id", noop_handler);
+        router.route(Method::POST, "/users", noop_handler);
+
+        assert!(router.find_handler(&Method::POST, "/users/7").is_none());
+        assert_eq!(router.allowed_methods("/users/7"), vec![Method::DELETE, Method::GET]);
+        assert!(router.allowed_methods("/nothing").is_empty());
+
+        let response = method_not_allowed_response(&router.allowed_methods("/users/7"));
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()["Allow"], "DELETE, GET");
+    }
+
+    #[test]
+    fn test_routes_are_sorted_with_handler_names() {
+        let mut router = PluginRouter::new();
+        router.route_named(Method::POST, "/users", "create_user", noop_handler);
+        router.route_named(Method::GET, "/users", "list_users", noop_handler);
+        router.route(Method::GET, "/health", noop_handler);
+
+        let routes = router.routes();
+        let summary: Vec<(&str, &str, Option<&str>)> = routes.iter()
+            .map(|r| (r.path.as_str(), r.method.as_str(), r.handler.as_deref()))
+            .collect();
+
+        assert_eq!(summary, vec![
+            ("/health", "GET", None),
+            ("/users", "GET", Some("list_users")),
+            ("/users", "POST", Some("create_user")),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_short_circuits_before_handler() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = counting_router(PluginRouter::new().with_middleware(bearer_token_auth("s3cret".to_string())), calls.clone());
+        let url = serve(router).await;
+        let client = reqwest::Client::new();
+
+        let missing = client.get(format!("{}/secret", url)).send().await.unwrap();
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        let wrong = client.get(format!("{}/secret", url)).bearer_auth("nope").send().await.unwrap();
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // Unknown paths still 404 rather than 401
+        let unknown = client.get(format!("{}/nothing", url)).send().await.unwrap();
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_passes_through_to_handler() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = PluginRouter::new()
+            .with_middleware(bearer_token_auth("s3cret".to_string()))
+            .with_middleware(rate_limit_requests(format!("test:{}:pass", std::process::id()), 2));
+        let url = serve(counting_router(router, calls.clone())).await;
+        let client = reqwest::Client::new();
+
+        for _ in 0..2 {
+            let ok = client.get(format!("{}/secret", url)).bearer_auth("s3cret").send().await.unwrap();
+            assert_eq!(ok.status(), StatusCode::OK);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let limited = client.get(format!("{}/secret", url)).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(limited.headers().contains_key("retry-after"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+/// Buckets shared by every plugin context, keyed "<plugin>:<key>"
+static LIMITER: Lazy<RateLimiter> = Lazy::new(RateLimiter::new);
+
+/// What `acquire` does when a bucket is empty
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitPolicy {
+    /// Sleep until a token is available
+    #[default]
+    Wait,
+    /// Fail straight away with `RateLimited`
+    Error,
+}
+
+/// Limit for one key, e.g. `rate_limits.<key>` in the plugin config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RateLimitConfig {
+    pub per_minute: u32,
+    #[serde(default)]
+    pub policy: LimitPolicy,
+}
+
+/// Returned (inside anyhow) when a bucket is empty under `LimitPolicy::Error`
+#[derive(Debug, Clone)]
+pub struct RateLimited {
+    pub key: String,
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rate limit for '{}' exceeded; retry in {} ms", self.key, self.retry_after.as_millis())
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Token bucket holding up to `per_minute` tokens, refilled continuously
+struct TokenBucket {
+    per_minute: u32,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        Self { per_minute, tokens: per_minute as f64, updated: now }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        let refill_per_sec = self.per_minute as f64 / 60.0;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * refill_per_sec).min(self.per_minute as f64);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / refill_per_sec))
+        }
+    }
+}
+
+/// Client-side throttling for calls to third-party APIs
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self { buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Take a token from `key`'s bucket at `now`, or return the wait until the next one
+    ///
+    /// Changing `per_minute` for a key starts it over with a full bucket.
+    pub fn try_acquire_at(&self, key: &str, per_minute: u32, now: Instant) -> Result<(), Duration> {
+        if per_minute == 0 {
+            return Err(Duration::MAX);
+        }
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(per_minute, now));
+        if bucket.per_minute != per_minute {
+            *bucket = TokenBucket::new(per_minute, now);
+        }
+        bucket.take(now)
+    }
+
+    /// Take a token from `key`'s bucket, waiting or failing per `limit.policy`
+    pub async fn acquire(&self, key: &str, limit: RateLimitConfig) -> anyhow::Result<()> {
+        loop {
+            match self.try_acquire_at(key, limit.per_minute, Instant::now()) {
+                Ok(()) => return Ok(()),
+                Err(retry_after) if limit.policy == LimitPolicy::Error || retry_after == Duration::MAX => {
+                    return Err(RateLimited { key: key.to_string(), retry_after }.into());
+                }
+                Err(retry_after) => tokio::time::sleep(retry_after).await,
+            }
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The process-wide limiter used by `PluginContext::rate_limit`
+pub fn shared() -> &'static RateLimiter {
+    &LIMITER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_per_minute_then_refills() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("withings", 3, start).is_ok());
+        }
+        // N+1 within the window: empty, one token every 20s
+        let wait = limiter.try_acquire_at("withings", 3, start).unwrap_err();
+        assert!(wait > Duration::from_millis(19_900) && wait < Duration::from_millis(20_100));
+
+        // Keys don't share buckets
+        assert!(limiter.try_acquire_at("hue", 3, start).is_ok());
+
+        assert!(limiter.try_acquire_at("withings", 3, start + Duration::from_secs(21)).is_ok());
+        assert!(limiter.try_acquire_at("withings", 3, start + Duration::from_secs(21)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_fails_fast() {
+        let limiter = RateLimiter::new();
+        let limit = RateLimitConfig { per_minute: 2, policy: LimitPolicy::Error };
+
+        limiter.acquire("hue", limit).await.unwrap();
+        limiter.acquire("hue", limit).await.unwrap();
+        let err = limiter.acquire("hue", limit).await.unwrap_err();
+        let limited = err.downcast_ref::<RateLimited>().unwrap();
+        assert_eq!(limited.key, "hue");
+        assert!(limited.retry_after > Duration::from_secs(29));
+    }
+
+    #[tokio::test]
+    async fn test_wait_policy_blocks_until_refill() {
+        let limiter = RateLimiter::new();
+        // 600/min = one token every 100ms
+        let limit = RateLimitConfig { per_minute: 600, policy: LimitPolicy::Wait };
+        for _ in 0..600 {
+            limiter.acquire("twitch", limit).await.unwrap();
+        }
+
+        let started = Instant::now();
+        limiter.acquire("twitch", limit).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn test_config_policy_defaults_to_wait() {
+        let config: RateLimitConfig = serde_json::from_value(serde_json::json!({ "per_minute": 60 })).unwrap();
+        assert_eq!(config.policy, LimitPolicy::Wait);
+    }
+}
@@ -1,7 +1,15 @@
 use hyper::{Request, Response, StatusCode, body::Incoming};
+use hyper::header::{HeaderMap, HeaderValue, ACCESS_CONTROL_ALLOW_ORIGIN, AUTHORIZATION, CONTENT_TYPE, VARY};
 use hyper::body::Bytes;
 use http_body_util::{Full, combinators::BoxBody};
 use std::convert::Infallible;
+use once_cell::sync::Lazy;
+
+/// Allowed CORS origins from BRIDGE_CORS_ORIGINS (comma-separated, default "*")
+/// `None` means every origin is allowed
+static CORS_ORIGINS: Lazy<Option<Vec<String>>> = Lazy::new(|| {
+    parse_cors_origins(&std::env::var("BRIDGE_CORS_ORIGINS").unwrap_or_else(|_| "*".to_string()))
+});
 
 /// Read JSON body from request
 pub async fn read_json_body(req: Request<Incoming>) -> Result<serde_json::Value, String> {
@@ -32,7 +40,6 @@ pub fn json_response<T: serde::Serialize>(data: &T) -> Response<BoxBody<Bytes, I
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
@@ -43,7 +50,6 @@ pub fn error_response(status: StatusCode, message: &str) -> Response<BoxBody<Byt
     Response::builder()
         .status(status)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
@@ -62,14 +68,446 @@ pub fn bytes_body(bytes: Vec<u8>) -> BoxBody<Bytes, Infallible> {
     BoxBody::new(Full::new(Bytes::from(bytes)).map_err(|err: Infallible| match err {}))
 }
 
+/// Result of matching a `Range` header against a body length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// No (usable) range - send the whole body
+    Full,
+    /// Inclusive byte range to send with 206
+    Partial { start: u64, end: u64 },
+    /// Range lies outside the body - answer 416
+    Unsatisfiable,
+}
+
+/// Parse a single `bytes=` range ("bytes=0-499", "bytes=500-", "bytes=-500")
+/// Multiple ranges and malformed headers fall back to the full body.
+pub fn parse_byte_range(header: Option<&str>, len: u64) -> ByteRange {
+    let spec = match header.and_then(|h| h.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Full,
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(parts) => parts,
+        None => return ByteRange::Full,
+    };
+
+    match (start.parse::<u64>().ok(), end.parse::<u64>().ok()) {
+        // Suffix range: the last N bytes
+        (None, Some(suffix)) if start.is_empty() => {
+            if suffix == 0 || len == 0 {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial { start: len.saturating_sub(suffix), end: len - 1 }
+            }
+        }
+        (Some(start), None) if end.is_empty() => {
+            if start >= len {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial { start, end: len - 1 }
+            }
+        }
+        (Some(start), Some(end)) if start <= end => {
+            if start >= len {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial { start, end: end.min(len - 1) }
+            }
+        }
+        _ => ByteRange::Full,
+    }
+}
+
+/// Finish a response for `body`, honouring the request's `Range` header (206/416)
+pub fn range_response(builder: hyper::http::response::Builder, body: Bytes, range: Option<&str>) -> Response<BoxBody<Bytes, Infallible>> {
+    use http_body_util::BodyExt;
+
+    let len = body.len() as u64;
+    let builder = builder.header("Accept-Ranges", "bytes");
+
+    let (builder, body) = match parse_byte_range(range, len) {
+        ByteRange::Full => (builder.status(StatusCode::OK), body),
+        ByteRange::Partial { start, end } => (
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header("Content-Range", format!("bytes {}-{}/{}", start, end, len)),
+            body.slice(start as usize..=end as usize),
+        ),
+        ByteRange::Unsatisfiable => (
+            builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header("Content-Range", format!("bytes */{}", len)),
+            Bytes::new(),
+        ),
+    };
+
+    builder
+        .body(BoxBody::new(Full::new(body).map_err(|err: Infallible| match err {})))
+        .unwrap()
+}
+
+/// Parse a comma-separated origin allowlist; `None` if it allows everything ("*" or empty)
+pub fn parse_cors_origins(value: &str) -> Option<Vec<String>> {
+    let origins: Vec<String> = value.split(',')
+        .map(|origin| origin.trim().trim_end_matches('/').to_string())
+        .filter(|origin| !origin.is_empty())
+        .collect();
+
+    if origins.is_empty() || origins.iter().any(|origin| origin == "*") {
+        None
+    } else {
+        Some(origins)
+    }
+}
+
+/// Whether BRIDGE_CORS_ORIGINS allows every origin
+pub fn cors_allows_any_origin() -> bool {
+    CORS_ORIGINS.is_none()
+}
+
+/// Apply the BRIDGE_CORS_ORIGINS policy to an outgoing response
+///
+/// Called once per response by the static and API servers, so handlers and
+/// plugin routes don't set the header themselves. Without an allowlist this
+/// adds `*` unless the response already names an origin; with one the
+/// request's `Origin` is echoed back only if it's listed, with `Vary: Origin`.
+pub fn apply_cors<B>(origin: Option<&str>, response: &mut Response<B>) {
+    apply_cors_policy(CORS_ORIGINS.as_deref(), origin, response.headers_mut());
+}
+
+fn apply_cors_policy(allowed: Option<&[String]>, origin: Option<&str>, headers: &mut HeaderMap) {
+    // No allowlist: any origin, unless a plugin chose its own
+    let allowed = match allowed {
+        Some(allowed) => allowed,
+        None => {
+            headers.entry(ACCESS_CONTROL_ALLOW_ORIGIN).or_insert(HeaderValue::from_static("*"));
+            return;
+        }
+    };
+
+    headers.remove(ACCESS_CONTROL_ALLOW_ORIGIN);
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+
+    let origin = origin.filter(|origin| allowed.iter().any(|a| a == origin.trim_end_matches('/')));
+    if let Some(value) = origin.and_then(|origin| HeaderValue::from_str(origin).ok()) {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, value);
+    }
+}
+
 /// Handle CORS preflight request
 pub async fn cors_preflight() -> Response<BoxBody<Bytes, Infallible>> {
     Response::builder()
         .status(StatusCode::OK)
-        .header("Access-Control-Allow-Origin", "*")
         .header("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS")
         .header("Access-Control-Allow-Headers", "Content-Type, Authorization")
         .header("Access-Control-Max-Age", "86400")
         .body(full_body(""))
         .unwrap()
 }
+
+/// Check a request for `Authorization: Bearer <token>`
+/// Returns a 401 response if the token is missing or wrong, None if it matches
+pub fn check_bearer_token<B>(req: &Request<B>, expected: &str) -> Option<Response<BoxBody<Bytes, Infallible>>> {
+    let provided = req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if token == expected => None,
+        Some(_) => Some(error_response(StatusCode::UNAUTHORIZED, "Invalid bearer token")),
+        None => Some(error_response(StatusCode::UNAUTHORIZED, "Missing bearer token")),
+    }
+}
+
+/// Check a state-changing admin request (install, enable, reload, batch)
+///
+/// Requires the bearer token when one is configured, and either
+/// `Content-Type: application/json` or an `X-Requested-With` header. Neither
+/// can be sent cross-site without a CORS preflight, so a page the user visits
+/// can't trigger the request with a plain form or `fetch` in no-cors mode.
+/// Returns a 401/403 response if the request is refused, None if it may proceed.
+pub fn check_admin_request<B>(req: &Request<B>, api_token: Option<&str>) -> Option<Response<BoxBody<Bytes, Infallible>>> {
+    if let Some(expected) = api_token {
+        if let Some(response) = check_bearer_token(req, expected) {
+            return Some(response);
+        }
+    }
+
+    let json_body = req.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or("").trim().eq_ignore_ascii_case("application/json"))
+        .unwrap_or(false);
+    if json_body || req.headers().contains_key("x-requested-with") {
+        None
+    } else {
+        Some(error_response(StatusCode::FORBIDDEN, "Requires Content-Type: application/json or an X-Requested-With header"))
+    }
+}
+
+/// Middleware that requires `Authorization: Bearer <token>` on every route
+pub fn bearer_token_auth(expected: String) -> impl Fn(&Request<Incoming>) -> Option<Response<BoxBody<Bytes, Infallible>>> + Send + Sync + 'static {
+    move |req| check_bearer_token(req, &expected)
+}
+
+/// Middleware that allows each client IP `per_minute` requests across all
+/// routes, answering 429 with `Retry-After` once its bucket is empty
+pub fn rate_limit_requests(prefix: String, per_minute: u32) -> impl Fn(&Request<Incoming>) -> Option<Response<BoxBody<Bytes, Infallible>>> + Send + Sync + 'static {
+    move |req| {
+        let key = rate_limit_key(&prefix, req);
+        let retry_after = crate::bridge::core::rate_limit::shared()
+            .try_acquire_at(&key, per_minute, std::time::Instant::now())
+            .err()?;
+        let mut response = error_response(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded");
+        let seconds = retry_after.as_secs_f64().ceil().min(u32::MAX as f64) as u64;
+        if let Ok(value) = HeaderValue::from_str(&seconds.to_string()) {
+            response.headers_mut().insert(hyper::header::RETRY_AFTER, value);
+        }
+        Some(response)
+    }
+}
+
+/// Rate limit bucket for a request: `{prefix}:{client ip}`
+/// The accept loop stores the peer address in the request extensions;
+/// requests without one share an "unknown" bucket.
+pub fn rate_limit_key<B>(prefix: &str, req: &Request<B>) -> String {
+    match req.extensions().get::<std::net::SocketAddr>() {
+        Some(peer) => format!("{}:{}", prefix, peer.ip()),
+        None => format!("{}:unknown", prefix),
+    }
+}
+
+/// Strong ETag for a response body (quoted FNV-1a hash of the content)
+pub fn content_etag(body: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in body {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("\"{:016x}-{:x}\"", hash, body.len())
+}
+
+/// Whether an `If-None-Match` header matches `etag`
+/// Uses weak comparison, as RFC 9110 requires for `If-None-Match`
+pub fn etag_matches(if_none_match: Option<&str>, etag: &str) -> bool {
+    let Some(header) = if_none_match else { return false };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    header.split(',').any(|candidate| {
+        candidate.trim() == "*" || opaque(candidate) == opaque(etag)
+    })
+}
+
+/// 304 Not Modified for a matching `If-None-Match`
+pub fn not_modified(etag: &str) -> Response<BoxBody<Bytes, Infallible>> {
+    Response::builder()
+        .status(StatusCode::NOT_MODIFIED)
+        .header("ETag", etag)
+        .body(full_body(""))
+        .unwrap()
+}
+
+/// Content type of an upload, detected from its leading bytes
+/// Without magic bytes only a declared `text/plain`, `text/csv` or
+/// `application/json` is believed; anything else is `application/octet-stream`.
+pub fn sniff_content_type(bytes: &[u8], declared: &str) -> String {
+    if let Some(kind) = infer::get(bytes) {
+        return kind.mime_type().to_string();
+    }
+
+    let declared = declared.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    match declared.as_str() {
+        "text/plain" | "text/csv" | "application/json" => declared,
+        _ => "application/octet-stream".to_string(),
+    }
+}
+
+/// Whether `content_type` is on an allowlist ("image/png", "image/*", "*")
+/// An empty allowlist allows everything
+pub fn content_type_allowed(content_type: &str, allowed: &[String]) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+
+    allowed.iter().any(|pattern| {
+        pattern == "*"
+            || pattern.eq_ignore_ascii_case(content_type)
+            || pattern.strip_suffix("/*")
+                .map(|prefix| content_type.split('/').next() == Some(prefix))
+                .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_sniff_ignores_mislabeled_content_type() {
+        assert_eq!(sniff_content_type(PNG_HEADER, "text/plain"), "image/png");
+        assert_eq!(sniff_content_type(b"hello world", "text/plain; charset=utf-8"), "text/plain");
+        assert_eq!(sniff_content_type(b"hello world", "image/png"), "application/octet-stream");
+        assert_eq!(sniff_content_type(b"{\"a\": 1}", "application/json"), "application/json");
+    }
+
+    #[test]
+    fn test_sniff_refuses_active_text_types() {
+        let script = b"<script>alert(document.cookie)</script>";
+        assert_eq!(sniff_content_type(script, "text/html"), "application/octet-stream");
+        assert_eq!(sniff_content_type(script, "image/svg+xml"), "application/octet-stream");
+        assert_eq!(sniff_content_type(script, "application/javascript"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_etag_matching() {
+        let etag = content_etag(b"export default {}");
+        assert_eq!(etag, content_etag(b"export default {}"));
+        assert_ne!(etag, content_etag(b"export default { a: 1 }"));
+
+        assert!(etag_matches(Some(&etag), &etag));
+        assert!(etag_matches(Some(&format!("\"other\", W/{}", etag)), &etag));
+        assert!(etag_matches(Some("*"), &etag));
+        assert!(!etag_matches(Some("\"other\""), &etag));
+        assert!(!etag_matches(None, &etag));
+        assert_eq!(not_modified(&etag).status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[test]
+    fn test_content_type_allowlist() {
+        let allowed = vec!["image/*".to_string(), "application/pdf".to_string()];
+        assert!(content_type_allowed("image/png", &allowed));
+        assert!(content_type_allowed("application/pdf", &allowed));
+        assert!(!content_type_allowed("application/x-msdownload", &allowed));
+        assert!(content_type_allowed("application/x-msdownload", &[]));
+    }
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range(Some("bytes=0-499"), 1000), ByteRange::Partial { start: 0, end: 499 });
+        assert_eq!(parse_byte_range(Some("bytes=500-"), 1000), ByteRange::Partial { start: 500, end: 999 });
+        assert_eq!(parse_byte_range(Some("bytes=-100"), 1000), ByteRange::Partial { start: 900, end: 999 });
+        assert_eq!(parse_byte_range(Some("bytes=900-5000"), 1000), ByteRange::Partial { start: 900, end: 999 });
+        assert_eq!(parse_byte_range(Some("bytes=1000-"), 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_byte_range(Some("bytes=0-1,5-9"), 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("items=0-1"), 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(None, 1000), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_range_response_statuses() {
+        let body = Bytes::from(vec![7u8; 1000]);
+
+        let partial = range_response(Response::builder(), body.clone(), Some("bytes=500-"));
+        assert_eq!(partial.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(partial.headers()["content-range"], "bytes 500-999/1000");
+        assert_eq!(partial.headers()["accept-ranges"], "bytes");
+
+        let out_of_bounds = range_response(Response::builder(), body.clone(), Some("bytes=2000-3000"));
+        assert_eq!(out_of_bounds.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(out_of_bounds.headers()["content-range"], "bytes */1000");
+
+        assert_eq!(range_response(Response::builder(), body, None).status(), StatusCode::OK);
+    }
+
+    fn cors_headers(allowed: &str, origin: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        apply_cors_policy(parse_cors_origins(allowed).as_deref(), origin, &mut headers);
+        headers
+    }
+
+    #[test]
+    fn test_cors_wildcard_allows_any_origin() {
+        let headers = cors_headers("*", Some("http://evil.example"));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert!(headers.get(VARY).is_none());
+
+        // An origin a plugin set itself is kept
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("http://localhost:3000"));
+        apply_cors_policy(None, Some("http://evil.example"), &mut headers);
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "http://localhost:3000");
+    }
+
+    #[test]
+    fn test_cors_allowlist_echoes_matching_origin() {
+        let headers = cors_headers("http://localhost:3000, http://192.168.1.20:3000/", Some("http://192.168.1.20:3000"));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "http://192.168.1.20:3000");
+        assert_eq!(headers[VARY], "Origin");
+    }
+
+    #[test]
+    fn test_cors_allowlist_drops_other_origins() {
+        assert!(cors_headers("http://localhost:3000", Some("http://evil.example")).get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        assert!(cors_headers("http://localhost:3000", None).get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    fn request_with_auth(value: Option<&str>) -> Request<()> {
+        let mut builder = Request::builder().uri("/secret");
+        if let Some(value) = value {
+            builder = builder.header(AUTHORIZATION, value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn test_bearer_token_passes_through_on_match() {
+        assert!(check_bearer_token(&request_with_auth(Some("Bearer s3cret")), "s3cret").is_none());
+    }
+
+    #[test]
+    fn test_bearer_token_short_circuits_with_401() {
+        let wrong = check_bearer_token(&request_with_auth(Some("Bearer nope")), "s3cret").unwrap();
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+        let missing = check_bearer_token(&request_with_auth(None), "s3cret").unwrap();
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+        let wrong_scheme = check_bearer_token(&request_with_auth(Some("Basic czNjcmV0")), "s3cret").unwrap();
+        assert_eq!(wrong_scheme.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn admin_request(headers: &[(&str, &str)]) -> Request<()> {
+        let mut builder = Request::builder().method("POST").uri("/api/plugins/demo/enable");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn test_admin_request_needs_preflighted_header() {
+        assert!(check_admin_request(&admin_request(&[("Content-Type", "application/json; charset=utf-8")]), None).is_none());
+        assert!(check_admin_request(&admin_request(&[("X-Requested-With", "webarcade")]), None).is_none());
+
+        let form = check_admin_request(&admin_request(&[("Content-Type", "text/plain")]), None).unwrap();
+        assert_eq!(form.status(), StatusCode::FORBIDDEN);
+        let bare = check_admin_request(&admin_request(&[]), None).unwrap();
+        assert_eq!(bare.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_admin_request_needs_token_when_configured() {
+        let no_token = check_admin_request(&admin_request(&[("Content-Type", "application/json")]), Some("s3cret")).unwrap();
+        assert_eq!(no_token.status(), StatusCode::UNAUTHORIZED);
+
+        let authorized = admin_request(&[("Content-Type", "application/json"), ("Authorization", "Bearer s3cret")]);
+        assert!(check_admin_request(&authorized, Some("s3cret")).is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_key_is_per_client_ip() {
+        let from = |peer: &str| {
+            let mut req = Request::builder().uri("/secret").body(()).unwrap();
+            req.extensions_mut().insert(peer.parse::<std::net::SocketAddr>().unwrap());
+            req
+        };
+
+        assert_eq!(rate_limit_key("routes:hue", &from("10.0.0.1:5000")), "routes:hue:10.0.0.1");
+        // Same IP from another port shares the bucket
+        assert_eq!(rate_limit_key("routes:hue", &from("10.0.0.1:6000")), "routes:hue:10.0.0.1");
+        assert_eq!(rate_limit_key("routes:hue", &from("10.0.0.2:5000")), "routes:hue:10.0.0.2");
+        assert_eq!(rate_limit_key("routes:hue", &Request::new(())), "routes:hue:unknown");
+    }
+}
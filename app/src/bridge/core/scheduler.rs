@@ -0,0 +1,503 @@
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, TimeZone, Timelike, Utc};
+use serde_json::Value;
+use crate::bridge::core::database;
+use crate::bridge::core::events::EventBus;
+
+/// Longest the scheduler sleeps between checks, so schedules added while it
+/// sleeps are picked up promptly
+const MAX_TICK: Duration = Duration::from_secs(1);
+
+/// How far ahead a cron expression is searched before it's treated as never firing
+const CRON_SEARCH_YEARS: i32 = 5;
+
+/// Longest `every` interval accepted
+const MAX_INTERVAL: Duration = Duration::from_secs(366 * 86_400);
+
+/// Source of the current time (swapped for a manual clock in tests)
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// When a schedule fires
+///
+/// - `every 500ms`, `every 30s`, `every 5m`, `every 1h`, `every 1d` - fixed interval
+/// - five-field cron (`minute hour day-of-month month day-of-week`), evaluated
+///   in UTC. Fields take `*`, numbers, ranges (`1-5`), lists (`1,15`) and steps
+///   (`*/15`, `0-30/10`). Day-of-week is 0-6 with 0 (or 7) = Sunday.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Every(Duration),
+    Cron(CronExpr),
+}
+
+impl Schedule {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        match spec.strip_prefix("every ") {
+            Some(interval) => parse_interval(interval.trim()).map(Schedule::Every),
+            None => CronExpr::parse(spec).map(Schedule::Cron),
+        }
+    }
+
+    /// First time strictly after `after` that this schedule fires
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Every(interval) => after.checked_add_signed(ChronoDuration::from_std(*interval).ok()?),
+            Schedule::Cron(cron) => cron.next_after(after),
+        }
+    }
+}
+
+fn parse_interval(interval: &str) -> Result<Duration> {
+    let split = interval.find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Interval '{}' needs a unit (ms, s, m, h, d)", interval))?;
+    let (amount, unit) = interval.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| anyhow!("Invalid interval '{}'", interval))?;
+
+    let too_long = || anyhow!("Interval '{}' is longer than {} days", interval, MAX_INTERVAL.as_secs() / 86_400);
+    let seconds = |multiplier: u64| amount.checked_mul(multiplier).map(Duration::from_secs).ok_or_else(too_long);
+    let duration = match unit.trim() {
+        "ms" => Duration::from_millis(amount),
+        "s" => Duration::from_secs(amount),
+        "m" => seconds(60)?,
+        "h" => seconds(3600)?,
+        "d" => seconds(86_400)?,
+        other => return Err(anyhow!("Unknown interval unit '{}'", other)),
+    };
+
+    if duration.is_zero() {
+        return Err(anyhow!("Interval must be greater than zero"));
+    }
+    if duration > MAX_INTERVAL {
+        return Err(too_long());
+    }
+    Ok(duration)
+}
+
+/// A parsed five-field cron expression (each field is a bitmask of allowed values)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether day-of-month / day-of-week were `*` (cron matches either
+    /// restricted day field, but both when only one is restricted)
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronExpr {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(anyhow!("Cron expression '{}' must have 5 fields", spec));
+        }
+
+        let mut days_of_week = parse_cron_field(fields[4], 0, 7)?;
+        // 7 is also Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+            days_of_week &= !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_cron_field(fields[0], 0, 59)?,
+            hours: parse_cron_field(fields[1], 0, 23)?,
+            days_of_month: parse_cron_field(fields[2], 1, 31)?,
+            months: parse_cron_field(fields[3], 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    fn day_matches(&self, t: &DateTime<Utc>) -> bool {
+        let dom = self.days_of_month & (1 << t.day()) != 0;
+        let dow = self.days_of_week & (1 << t.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        let limit = start + ChronoDuration::days(366 * CRON_SEARCH_YEARS as i64);
+        let mut t = start;
+
+        while t < limit {
+            if self.months & (1 << t.month()) == 0 {
+                // Jump to the first minute of next month
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(&t) {
+                t = t.with_hour(0)?.with_minute(0)? + ChronoDuration::days(1);
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + ChronoDuration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += ChronoDuration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+
+        None
+    }
+}
+
+/// Parse one cron field into a bitmask of the values it allows
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| anyhow!("Invalid cron step in '{}'", part))?;
+                if step == 0 {
+                    return Err(anyhow!("Cron step must be greater than zero in '{}'", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_cron_value(start, part)?, parse_cron_value(end, part)?)
+        } else {
+            let value = parse_cron_value(range, part)?;
+            // "5/15" means every 15 starting at 5
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(anyhow!("Cron field '{}' is out of range {}-{}", part, min, max));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_cron_value(value: &str, part: &str) -> Result<u32> {
+    value.parse().map_err(|_| anyhow!("Invalid cron value in '{}'", part))
+}
+
+/// A persisted schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledEvent {
+    pub id: i64,
+    pub plugin_id: String,
+    pub spec: String,
+    pub topic: String,
+    pub payload: Value,
+    pub next_run: DateTime<Utc>,
+}
+
+fn ensure_schedules_table(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _schedules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            plugin_id TEXT NOT NULL,
+            spec TEXT NOT NULL,
+            topic TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            next_run INTEGER NOT NULL,
+            UNIQUE (plugin_id, topic, spec)
+        )",
+    )?;
+    Ok(())
+}
+
+/// Create or update a schedule, returning its id
+///
+/// Schedules are keyed by (plugin, topic, spec), so a plugin can call this on
+/// every start without piling up duplicates; only the payload is updated and
+/// the next run time is kept.
+pub fn upsert_schedule(
+    conn: &rusqlite::Connection,
+    plugin_id: &str,
+    spec: &str,
+    topic: &str,
+    payload: &Value,
+    now: DateTime<Utc>,
+) -> Result<i64> {
+    let schedule = Schedule::parse(spec)?;
+    let next_run = schedule.next_after(now)
+        .ok_or_else(|| anyhow!("Schedule '{}' never fires", spec))?;
+
+    ensure_schedules_table(conn)?;
+    conn.execute(
+        "INSERT INTO _schedules (plugin_id, spec, topic, payload, next_run) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (plugin_id, topic, spec) DO UPDATE SET payload = excluded.payload",
+        rusqlite::params![plugin_id, spec, topic, payload.to_string(), next_run.timestamp_millis()],
+    )?;
+
+    let id = conn.query_row(
+        "SELECT id FROM _schedules WHERE plugin_id = ?1 AND topic = ?2 AND spec = ?3",
+        rusqlite::params![plugin_id, topic, spec],
+        |row| row.get(0),
+    )?;
+    Ok(id)
+}
+
+/// Delete one of a plugin's schedules. Returns true if it existed.
+pub fn remove_schedule(conn: &rusqlite::Connection, plugin_id: &str, id: i64) -> Result<bool> {
+    ensure_schedules_table(conn)?;
+    let removed = conn.execute(
+        "DELETE FROM _schedules WHERE plugin_id = ?1 AND id = ?2",
+        rusqlite::params![plugin_id, id],
+    )?;
+    Ok(removed > 0)
+}
+
+/// Every schedule belonging to a plugin, by id
+pub fn list_schedules(conn: &rusqlite::Connection, plugin_id: &str) -> Result<Vec<ScheduledEvent>> {
+    ensure_schedules_table(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, plugin_id, spec, topic, payload, next_run FROM _schedules WHERE plugin_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map([plugin_id], row_to_schedule)?;
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+fn row_to_schedule(row: &rusqlite::Row) -> rusqlite::Result<ScheduledEvent> {
+    let payload: String = row.get(4)?;
+    let next_run: i64 = row.get(5)?;
+    Ok(ScheduledEvent {
+        id: row.get(0)?,
+        plugin_id: row.get(1)?,
+        spec: row.get(2)?,
+        topic: row.get(3)?,
+        payload: serde_json::from_str(&payload).unwrap_or(Value::Null),
+        next_run: Utc.timestamp_millis_opt(next_run).single().unwrap_or_default(),
+    })
+}
+
+/// Fires persisted schedules as events on the bus
+///
+/// Schedules live in the `_schedules` table, so they survive restarts. Runs
+/// missed while the app was closed fire once on startup rather than once per
+/// missed slot.
+pub struct Scheduler {
+    event_bus: Arc<EventBus>,
+    db_path: String,
+    clock: Arc<dyn Clock>,
+}
+
+impl Scheduler {
+    pub fn new(event_bus: Arc<EventBus>, db_path: String) -> Self {
+        Self::with_clock(event_bus, db_path, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(event_bus: Arc<EventBus>, db_path: String, clock: Arc<dyn Clock>) -> Self {
+        Self { event_bus, db_path, clock }
+    }
+
+    /// Publish every schedule that is due and move it to its next run.
+    /// Returns how many fired.
+    pub fn fire_due(&self) -> Result<usize> {
+        let now = self.clock.now();
+        let conn = database::get_pool(&self.db_path)?.get()?;
+        ensure_schedules_table(&conn)?;
+
+        let due: Vec<ScheduledEvent> = {
+            let mut stmt = conn.prepare(
+                "SELECT id, plugin_id, spec, topic, payload, next_run FROM _schedules WHERE next_run <= ?1 ORDER BY next_run",
+            )?;
+            let rows = stmt.query_map([now.timestamp_millis()], row_to_schedule)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        for scheduled in &due {
+            self.event_bus.publish_typed(&scheduled.plugin_id, &scheduled.topic, &scheduled.payload);
+
+            // Step from the slot that fired so intervals don't drift, but skip
+            // ahead rather than replaying slots that were missed
+            let next_run = Schedule::parse(&scheduled.spec).ok().and_then(|schedule| {
+                schedule.next_after(scheduled.next_run)
+                    .filter(|next| *next > now)
+                    .or_else(|| schedule.next_after(now))
+            });
+
+            match next_run {
+                Some(next_run) => {
+                    conn.execute(
+                        "UPDATE _schedules SET next_run = ?1 WHERE id = ?2",
+                        rusqlite::params![next_run.timestamp_millis(), scheduled.id],
+                    )?;
+                }
+                None => {
+                    log::warn!("⏰ Schedule {} ({}) will not fire again, removing it", scheduled.id, scheduled.spec);
+                    conn.execute("DELETE FROM _schedules WHERE id = ?1", [scheduled.id])?;
+                }
+            }
+        }
+
+        Ok(due.len())
+    }
+
+    /// How long to wait before the next check
+    fn next_tick(&self) -> Duration {
+        let next_run: Option<i64> = database::get_pool(&self.db_path)
+            .and_then(|pool| Ok(pool.get()?))
+            .and_then(|conn| {
+                ensure_schedules_table(&conn)?;
+                Ok(conn.query_row("SELECT MIN(next_run) FROM _schedules", [], |row| row.get(0))?)
+            })
+            .unwrap_or(None);
+
+        next_run
+            .map(|ms| (ms - self.clock.now().timestamp_millis()).max(10) as u64)
+            .map(|ms| Duration::from_millis(ms).min(MAX_TICK))
+            .unwrap_or(MAX_TICK)
+    }
+
+    /// Fire schedules until `shutdown` flips to true
+    pub async fn run(self: Arc<Self>, mut shutdown: tokio::sync::watch::Receiver<bool>) {
+        log::info!("⏰ Scheduler started");
+        while !*shutdown.borrow() {
+            let scheduler = self.clone();
+            let tick = tokio::task::spawn_blocking(move || {
+                if let Err(e) = scheduler.fire_due() {
+                    log::error!("⏰ Scheduler failed to fire events: {}", e);
+                }
+                scheduler.next_tick()
+            }).await.unwrap_or(MAX_TICK);
+
+            tokio::select! {
+                _ = tokio::time::sleep(tick) => {}
+                _ = shutdown.changed() => {}
+            }
+        }
+        log::info!("⏰ Scheduler stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct ManualClock(Mutex<DateTime<Utc>>);
+
+    impl ManualClock {
+        fn advance(&self, by: ChronoDuration) {
+            let mut now = self.0.lock().unwrap();
+            *now += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_intervals_and_cron() {
+        assert_eq!(Schedule::parse("every 500ms").unwrap(), Schedule::Every(Duration::from_millis(500)));
+        assert_eq!(Schedule::parse("every 1h").unwrap(), Schedule::Every(Duration::from_secs(3600)));
+        assert!(Schedule::parse("every 0s").is_err());
+        assert!(Schedule::parse("every 5 weeks").is_err());
+        assert!(Schedule::parse("*/15 * * *").is_err());
+        assert!(Schedule::parse("61 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_huge_intervals_are_rejected() {
+        assert!(Schedule::parse("every 1000000000d").is_err());
+        assert!(Schedule::parse("every 18446744073709551615h").is_err());
+        assert!(Schedule::parse("every 367d").is_err());
+        assert_eq!(Schedule::parse("every 366d").unwrap(), Schedule::Every(MAX_INTERVAL));
+
+        let far = Schedule::Every(Duration::from_secs(u64::MAX / 2));
+        assert_eq!(far.next_after(at(2024, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let every_15 = Schedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(every_15.next_after(at(2024, 1, 1, 10, 7)), Some(at(2024, 1, 1, 10, 15)));
+        assert_eq!(every_15.next_after(at(2024, 1, 1, 10, 45)), Some(at(2024, 1, 1, 11, 0)));
+
+        // 09:30 on weekdays; 2024-01-06 is a Saturday
+        let weekdays = Schedule::parse("30 9 * * 1-5").unwrap();
+        assert_eq!(weekdays.next_after(at(2024, 1, 5, 9, 30)), Some(at(2024, 1, 8, 9, 30)));
+
+        // Midnight on Feb 29 only exists in leap years
+        let leap = Schedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(leap.next_after(at(2024, 3, 1, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+
+        // Sunday as 7
+        let sunday = Schedule::parse("0 12 * * 7").unwrap();
+        assert_eq!(sunday.next_after(at(2024, 1, 1, 0, 0)), Some(at(2024, 1, 7, 12, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_fires_due_events_with_manual_clock() {
+        let db_path = std::env::temp_dir()
+            .join(format!("webarcade_scheduler_test_{}.db", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let _ = std::fs::remove_file(&db_path);
+
+        let clock = Arc::new(ManualClock(Mutex::new(at(2024, 1, 1, 10, 0))));
+        let bus = Arc::new(EventBus::new());
+        let mut events = bus.subscribe();
+        let scheduler = Scheduler::with_clock(bus.clone(), db_path.clone(), clock.clone());
+
+        let conn = database::get_pool(&db_path).unwrap().get().unwrap();
+        let id = upsert_schedule(&conn, "withings", "every 1h", "withings.sync", &serde_json::json!({"full": false}), clock.now()).unwrap();
+        // Re-registering on the next start doesn't duplicate it
+        assert_eq!(upsert_schedule(&conn, "withings", "every 1h", "withings.sync", &Value::Null, clock.now()).unwrap(), id);
+
+        assert_eq!(scheduler.fire_due().unwrap(), 0);
+
+        clock.advance(ChronoDuration::minutes(60));
+        assert_eq!(scheduler.fire_due().unwrap(), 1);
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.event_type, "withings.sync");
+        assert_eq!(event.source_plugin, "withings");
+
+        // Already moved to the next hour
+        assert_eq!(scheduler.fire_due().unwrap(), 0);
+        assert_eq!(list_schedules(&conn, "withings").unwrap()[0].next_run, at(2024, 1, 1, 12, 0));
+
+        assert!(remove_schedule(&conn, "withings", id).unwrap());
+        clock.advance(ChronoDuration::hours(5));
+        assert_eq!(scheduler.fire_due().unwrap(), 0);
+
+        drop(conn);
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
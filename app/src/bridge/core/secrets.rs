@@ -0,0 +1,253 @@
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Keychain service/user under which the secrets key is stored
+const KEYCHAIN_SERVICE: &str = "webarcade";
+const KEYCHAIN_USER: &str = "plugin-secrets-key";
+
+/// Prefix of values sealed with the keychain key
+const KEYCHAIN_PREFIX: &str = "enc1k:";
+/// Prefix of values sealed with the machine-bound fallback key
+const MACHINE_PREFIX: &str = "enc1m:";
+
+const NONCE_LEN: usize = 12;
+
+/// Process-wide keys, loaded on first use
+static KEYS: Lazy<SecretKeys> = Lazy::new(SecretKeys::load);
+
+/// Keys used to seal plugin secrets
+///
+/// The keychain key is a random 256-bit key kept in the OS keychain
+/// (Keychain, Credential Manager, Secret Service). When no keychain is
+/// available values are sealed with a key derived from machine and user
+/// identifiers instead - that only obfuscates them against someone who
+/// copies the database file elsewhere, so it is logged loudly.
+pub struct SecretKeys {
+    keychain: Option<[u8; 32]>,
+    machine: [u8; 32],
+}
+
+impl SecretKeys {
+    fn load() -> Self {
+        let keychain = match load_keychain_key() {
+            Ok(key) => Some(key),
+            Err(e) => {
+                log::warn!("⚠️  OS keychain unavailable ({}); plugin secrets will only be obfuscated with a machine-bound key", e);
+                None
+            }
+        };
+        Self { keychain, machine: machine_key() }
+    }
+
+    /// Keys with an explicit keychain key (tests, tooling)
+    pub fn new(keychain: Option<[u8; 32]>, machine: [u8; 32]) -> Self {
+        Self { keychain, machine }
+    }
+
+    /// Whether new values are protected by the keychain key
+    pub fn has_keychain(&self) -> bool {
+        self.keychain.is_some()
+    }
+
+    /// Encrypt `plaintext`, binding it to `context` (e.g. "withings/access_token")
+    ///
+    /// The context is authenticated, so a sealed value copied to another
+    /// plugin or key fails to open.
+    pub fn seal(&self, context: &str, plaintext: &str) -> Result<String> {
+        let (prefix, key) = match &self.keychain {
+            Some(key) => (KEYCHAIN_PREFIX, key),
+            None => (MACHINE_PREFIX, &self.machine),
+        };
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: context.as_bytes() })
+            .map_err(|_| anyhow!("Failed to encrypt secret '{}'", context))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", prefix, BASE64.encode(sealed)))
+    }
+
+    /// Decrypt a value produced by `seal` with the same `context`
+    pub fn open(&self, context: &str, sealed: &str) -> Result<String> {
+        let (key, encoded) = if let Some(encoded) = sealed.strip_prefix(KEYCHAIN_PREFIX) {
+            let key = self.keychain.as_ref()
+                .ok_or_else(|| anyhow!("Secret '{}' was sealed with the OS keychain key, which is unavailable", context))?;
+            (key, encoded)
+        } else if let Some(encoded) = sealed.strip_prefix(MACHINE_PREFIX) {
+            (&self.machine, encoded)
+        } else {
+            return Err(anyhow!("Secret '{}' is not an encrypted value", context));
+        };
+
+        let raw = BASE64.decode(encoded).map_err(|e| anyhow!("Secret '{}' is corrupt: {}", context, e))?;
+        if raw.len() < NONCE_LEN {
+            return Err(anyhow!("Secret '{}' is corrupt: too short", context));
+        }
+        let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: context.as_bytes() })
+            .map_err(|_| anyhow!("Secret '{}' failed to decrypt (wrong key or tampered)", context))?;
+        String::from_utf8(plaintext).map_err(|e| anyhow!("Secret '{}' is not UTF-8: {}", context, e))
+    }
+}
+
+/// The process-wide keys
+pub fn keys() -> &'static SecretKeys {
+    &KEYS
+}
+
+/// Whether a stored value was produced by `seal` (vs. legacy plaintext)
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(KEYCHAIN_PREFIX) || value.starts_with(MACHINE_PREFIX)
+}
+
+/// Fetch the keychain key, creating it on first run
+fn load_keychain_key() -> Result<[u8; 32]> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER)?;
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = BASE64.decode(encoded.trim())?;
+            bytes.try_into().map_err(|_| anyhow!("Keychain entry is not a 256-bit key"))
+        }
+        Err(keyring::Error::NoEntry) => {
+            let mut key = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            entry.set_password(&BASE64.encode(key))?;
+            log::info!("🔐 Created plugin secrets key in the OS keychain");
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Key derived from stable machine and user identifiers (fallback only)
+fn machine_key() -> [u8; 32] {
+    let machine_id = std::fs::read_to_string("/etc/machine-id")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default();
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"webarcade-plugin-secrets-v1\0");
+    hasher.update(machine_id.trim().as_bytes());
+    hasher.update(b"\0");
+    hasher.update(user.as_bytes());
+    hasher.finalize().into()
+}
+
+// ==================== Storage ====================
+
+fn ensure_secrets_table(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS _plugin_secrets (
+            plugin_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (plugin_id, key)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Encrypt and store a secret for a plugin
+pub fn set_secret(conn: &rusqlite::Connection, keys: &SecretKeys, plugin_id: &str, key: &str, value: &str) -> Result<()> {
+    ensure_secrets_table(conn)?;
+    let sealed = keys.seal(&format!("{}/{}", plugin_id, key), value)?;
+    conn.execute(
+        "INSERT INTO _plugin_secrets (plugin_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(plugin_id, key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        rusqlite::params![plugin_id, key, sealed, chrono::Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+/// Load and decrypt a plugin's secret, `None` if it was never set
+pub fn get_secret(conn: &rusqlite::Connection, keys: &SecretKeys, plugin_id: &str, key: &str) -> Result<Option<String>> {
+    ensure_secrets_table(conn)?;
+    let sealed = conn.query_row(
+        "SELECT value FROM _plugin_secrets WHERE plugin_id = ?1 AND key = ?2",
+        [plugin_id, key],
+        |row| row.get::<_, String>(0),
+    );
+    match sealed {
+        Ok(sealed) => keys.open(&format!("{}/{}", plugin_id, key), &sealed).map(Some),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Remove a plugin's secret
+pub fn delete_secret(conn: &rusqlite::Connection, plugin_id: &str, key: &str) -> Result<bool> {
+    ensure_secrets_table(conn)?;
+    Ok(conn.execute("DELETE FROM _plugin_secrets WHERE plugin_id = ?1 AND key = ?2", [plugin_id, key])? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_keys(keychain: bool) -> SecretKeys {
+        SecretKeys::new(keychain.then_some([7u8; 32]), [9u8; 32])
+    }
+
+    #[test]
+    fn test_seal_round_trip_and_context_binding() {
+        let keys = test_keys(true);
+        let sealed = keys.seal("withings/access_token", "tok-123").unwrap();
+        assert!(sealed.starts_with(KEYCHAIN_PREFIX));
+        assert!(!sealed.contains("tok-123"));
+        assert_eq!(keys.open("withings/access_token", &sealed).unwrap(), "tok-123");
+
+        // Same plaintext seals differently each time
+        assert_ne!(keys.seal("withings/access_token", "tok-123").unwrap(), sealed);
+
+        // Moving the value to another plugin/key fails authentication
+        assert!(keys.open("hue/access_token", &sealed).is_err());
+        assert!(keys.open("withings/access_token", "tok-123").is_err());
+    }
+
+    #[test]
+    fn test_machine_fallback_stays_readable() {
+        let fallback = test_keys(false);
+        let sealed = fallback.seal("hue/username", "bridge-user").unwrap();
+        assert!(sealed.starts_with(MACHINE_PREFIX));
+
+        // Values written before a keychain became available still open
+        assert_eq!(test_keys(true).open("hue/username", &sealed).unwrap(), "bridge-user");
+
+        // Keychain-sealed values can't be opened without the keychain
+        let keychain_sealed = test_keys(true).seal("hue/username", "bridge-user").unwrap();
+        assert!(fallback.open("hue/username", &keychain_sealed).is_err());
+    }
+
+    #[test]
+    fn test_secret_storage() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let keys = test_keys(true);
+
+        assert_eq!(get_secret(&conn, &keys, "withings", "refresh_token").unwrap(), None);
+        set_secret(&conn, &keys, "withings", "refresh_token", "r-1").unwrap();
+        set_secret(&conn, &keys, "withings", "refresh_token", "r-2").unwrap();
+        assert_eq!(get_secret(&conn, &keys, "withings", "refresh_token").unwrap().as_deref(), Some("r-2"));
+
+        let stored: String = conn.query_row("SELECT value FROM _plugin_secrets", [], |row| row.get(0)).unwrap();
+        assert!(is_sealed(&stored));
+
+        assert!(delete_secret(&conn, "withings", "refresh_token").unwrap());
+        assert_eq!(get_secret(&conn, &keys, "withings", "refresh_token").unwrap(), None);
+    }
+}
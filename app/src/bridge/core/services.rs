@@ -2,7 +2,10 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use anyhow::Result;
 
@@ -12,15 +15,108 @@ pub type ServiceMethod = Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value>>
 /// Helper type for boxed futures
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// Code used when a service fails with a plain (non-`ServiceError`) error
+pub const GENERIC_ERROR_CODE: &str = "service_error";
+
+/// Structured error a service can return so callers can match on a stable code
+/// Recovered with `ServiceError::from_error`; other errors map to `GENERIC_ERROR_CODE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceError {
+    /// Stable, machine-readable code (e.g., "insufficient_funds")
+    pub code: String,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// Extra context for the caller
+    #[serde(default)]
+    pub details: Value,
+}
+
+impl ServiceError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            details: Value::Null,
+        }
+    }
+
+    /// Attach extra context (e.g., `{"balance": 5, "cost": 10}`)
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Recover the structured error from an `anyhow::Error`
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<ServiceError>() {
+            Some(service_error) => service_error.clone(),
+            None => Self::new(GENERIC_ERROR_CODE, error.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+/// Rows returned when a list service is called without a `limit`
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Upper bound on `limit` so one call can't pull an entire table
+pub const MAX_PAGE_LIMIT: i64 = 500;
+
+/// `limit`/`offset` read from a list service's input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Pagination {
+    /// Missing or invalid values fall back to the defaults; `limit` is clamped
+    /// to 1..=`MAX_PAGE_LIMIT` and `offset` to >= 0
+    pub fn from_input(input: &Value) -> Self {
+        let limit = input.get("limit").and_then(|v| v.as_i64()).unwrap_or(DEFAULT_PAGE_LIMIT);
+        let offset = input.get("offset").and_then(|v| v.as_i64()).unwrap_or(0);
+        Self {
+            limit: limit.clamp(1, MAX_PAGE_LIMIT),
+            offset: offset.max(0),
+        }
+    }
+
+    /// Wrap one page of results in the standard list envelope:
+    /// `{"items": [...], "total", "limit", "offset", "has_more"}`
+    pub fn response<T: Serialize>(&self, items: Vec<T>, total: i64) -> Value {
+        let has_more = self.offset + (items.len() as i64) < total;
+        serde_json::json!({
+            "items": items,
+            "total": total,
+            "limit": self.limit,
+            "offset": self.offset,
+            "has_more": has_more,
+        })
+    }
+}
+
 /// Service registry - plugins register services, other plugins call them
 pub struct ServiceRegistry {
     services: Arc<RwLock<HashMap<String, ServiceMethod>>>,
+
+    /// Log every call's service id and duration at debug level
+    tracing: AtomicBool,
 }
 
 impl ServiceRegistry {
     pub fn new() -> Self {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
+            tracing: AtomicBool::new(false),
         }
     }
 
@@ -45,12 +141,37 @@ impl ServiceRegistry {
         if let Some(handler) = services.get(service_id) {
             let handler = handler.clone();
             drop(services); // Release lock before calling handler
-            handler(input).await
+
+            if !self.tracing.load(Ordering::Relaxed) {
+                return handler(input).await;
+            }
+
+            let started = Instant::now();
+            let result = handler(input).await;
+            log::debug!(
+                "🔧 service {} {} in {:?}",
+                service_id,
+                if result.is_ok() { "ok" } else { "failed" },
+                started.elapsed()
+            );
+            result
         } else {
-            Err(anyhow::anyhow!("Service not found: {}", service_id))
+            Err(ServiceError::new("service_not_found", format!("Service not found: {}", service_id)).into())
         }
     }
 
+    /// Call several services concurrently
+    ///
+    /// Results come back in the same order as `calls`, one per call; a failing
+    /// call doesn't affect the others.
+    pub async fn call_batch(&self, calls: Vec<(String, Value)>) -> Vec<Result<Value>> {
+        futures_util::future::join_all(
+            calls.into_iter().map(|(service_id, input)| async move {
+                self.call(&service_id, input).await
+            })
+        ).await
+    }
+
     /// Check if service exists
     pub async fn has_service(&self, service_id: &str) -> bool {
         self.services.read().await.contains_key(service_id)
@@ -60,6 +181,33 @@ impl ServiceRegistry {
     pub async fn list_services(&self) -> Vec<String> {
         self.services.read().await.keys().cloned().collect()
     }
+
+    /// Remove every service registered by `plugin_id`, returning how many were removed
+    pub async fn unregister_plugin(&self, plugin_id: &str) -> usize {
+        let prefix = format!("{}.", plugin_id);
+        let mut services = self.services.write().await;
+        let before = services.len();
+        services.retain(|service_id, _| !service_id.starts_with(&prefix));
+        before - services.len()
+    }
+
+    /// Every registered service as a sorted `(plugin_id, service_name)` pair
+    pub async fn list(&self) -> Vec<(String, String)> {
+        let services = self.services.read().await;
+        let mut pairs: Vec<(String, String)> = services.keys()
+            .map(|service_id| match service_id.split_once('.') {
+                Some((plugin_id, name)) => (plugin_id.to_string(), name.to_string()),
+                None => (String::new(), service_id.clone()),
+            })
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// Turn debug-level call tracing on or off
+    pub fn set_tracing(&self, enabled: bool) {
+        self.tracing.store(enabled, Ordering::Relaxed);
+    }
 }
 
 impl Default for ServiceRegistry {
@@ -67,3 +215,80 @@ impl Default for ServiceRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_returns_plugin_and_service_pairs() {
+        let registry = ServiceRegistry::new();
+        registry.register("packs.open_pack", |input| async move { Ok(input) }).await;
+        registry.register("currency.get_balance", |_| async move { Ok(Value::from(0)) }).await;
+        registry.register("currency.spend", |_| async move { Ok(Value::Null) }).await;
+
+        assert_eq!(registry.list().await, vec![
+            ("currency".to_string(), "get_balance".to_string()),
+            ("currency".to_string(), "spend".to_string()),
+            ("packs".to_string(), "open_pack".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_call_propagates_service_error_code() {
+        let registry = ServiceRegistry::new();
+        registry.register("confessions.submit_confession", |_| async move {
+            Err(ServiceError::new("confession_too_short", "Confession must be at least 10 characters")
+                .with_details(serde_json::json!({ "min_length": 10 }))
+                .into())
+        }).await;
+
+        let err = registry.call("confessions.submit_confession", Value::Null).await.unwrap_err();
+        let service_error = ServiceError::from_error(&err);
+        assert_eq!(service_error.code, "confession_too_short");
+        assert_eq!(service_error.details["min_length"], 10);
+    }
+
+    #[test]
+    fn test_pagination_clamps_input_and_reports_has_more() {
+        let page = Pagination::from_input(&serde_json::json!({"limit": 10_000, "offset": -5}));
+        assert_eq!(page, Pagination { limit: MAX_PAGE_LIMIT, offset: 0 });
+        assert_eq!(Pagination::from_input(&Value::Null).limit, DEFAULT_PAGE_LIMIT);
+
+        let page = Pagination::from_input(&serde_json::json!({"limit": 2, "offset": 2}));
+        let response = page.response(vec![3, 4], 5);
+        assert_eq!(response["items"], serde_json::json!([3, 4]));
+        assert_eq!(response["has_more"], true);
+        assert_eq!(page.response(vec![5], 5)["has_more"], false);
+    }
+
+    #[tokio::test]
+    async fn test_call_batch_keeps_order_and_isolates_failures() {
+        let registry = ServiceRegistry::new();
+        registry.register("currency.get_balance", |_| async move { Ok(Value::from(25)) }).await;
+        registry.register("packs.list", |_| async move { Ok(serde_json::json!(["starter"])) }).await;
+
+        let results = registry.call_batch(vec![
+            ("currency.get_balance".to_string(), Value::Null),
+            ("missing.call".to_string(), Value::Null),
+            ("packs.list".to_string(), Value::Null),
+        ]).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &Value::from(25));
+        assert_eq!(ServiceError::from_error(results[1].as_ref().unwrap_err()).code, "service_not_found");
+        assert_eq!(results[2].as_ref().unwrap(), &serde_json::json!(["starter"]));
+    }
+
+    #[tokio::test]
+    async fn test_plain_errors_map_to_generic_code() {
+        let registry = ServiceRegistry::new();
+        registry.register("packs.open_pack", |_| async move { Err(anyhow::anyhow!("boom")) }).await;
+
+        let err = registry.call("packs.open_pack", Value::Null).await.unwrap_err();
+        assert_eq!(ServiceError::from_error(&err), ServiceError::new(GENERIC_ERROR_CODE, "boom"));
+
+        let missing = registry.call("packs.nope", Value::Null).await.unwrap_err();
+        assert_eq!(ServiceError::from_error(&missing).code, "service_not_found");
+    }
+}
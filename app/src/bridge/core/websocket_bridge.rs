@@ -1,11 +1,40 @@
+use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 use futures_util::{StreamExt, SinkExt};
 use std::sync::Arc;
 use tokio::sync::broadcast;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use anyhow::Result;
+use serde_json::Value;
 
 use super::EventBus;
+use super::events::topic_matches;
+
+/// Number of currently connected WebSocket clients
+static CONNECTION_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Keepalive timing for WebSocket clients
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    /// How often the server pings each client
+    interval: Duration,
+    /// How long past a missed ping a client may stay silent before it's closed
+    timeout: Duration,
+}
+
+const HEARTBEAT: Heartbeat = Heartbeat {
+    interval: Duration::from_secs(30),
+    timeout: Duration::from_secs(10),
+};
+
+/// A serialized event as fanned out to WebSocket clients
+struct WsEvent {
+    event_type: String,
+    json: String,
+}
 
 /// WebSocket bridge that forwards plugin events to connected WebSocket clients
 pub struct WebSocketBridge {
@@ -17,16 +46,21 @@ impl WebSocketBridge {
         Self { event_bus }
     }
 
-    pub async fn start(&self, port: String) -> Result<()> {
-        let addr = format!("127.0.0.1:{}", port);
-        let listener = TcpListener::bind(&addr).await?;
+    /// Number of currently connected WebSocket clients
+    pub fn connection_count() -> usize {
+        CONNECTION_COUNT.load(Ordering::Relaxed)
+    }
+
+    /// Serve WebSocket clients on `addr` until `shutdown` flips to true
+    pub async fn start(&self, addr: SocketAddr, mut shutdown: tokio::sync::watch::Receiver<bool>) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
         log::info!("📡 WebSocket server listening on ws://{}", addr);
 
         // Subscribe to ALL events from the event bus
         let mut global_events = self.event_bus.subscribe();
 
         // Broadcast channel for WebSocket clients
-        let (ws_tx, _) = broadcast::channel::<String>(1000);
+        let (ws_tx, _) = broadcast::channel::<Arc<WsEvent>>(1000);
         let ws_tx = Arc::new(ws_tx);
 
         // Spawn task to forward plugin events to WebSocket broadcast channel
@@ -35,23 +69,34 @@ impl WebSocketBridge {
             while let Ok(event) = global_events.recv().await {
                 // Serialize event to JSON
                 if let Ok(json) = serde_json::to_string(&event) {
-                    // Broadcast to all WebSocket clients
-                    let _ = ws_tx_clone.send(json);
+                    // Broadcast to all WebSocket clients (each filters by its subscriptions)
+                    let _ = ws_tx_clone.send(Arc::new(WsEvent { event_type: event.event_type, json }));
                 }
             }
         });
 
         // Accept WebSocket connections
         loop {
-            match listener.accept().await {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = shutdown.wait_for(|stop| *stop) => {
+                    log::info!("📡 WebSocket server stopped");
+                    return Ok(());
+                }
+            };
+            match accepted {
                 Ok((stream, addr)) => {
                     log::debug!("WebSocket connection from: {}", addr);
                     let ws_rx = ws_tx.subscribe();
 
                     tokio::spawn(async move {
-                        if let Err(e) = handle_websocket_client(stream, ws_rx).await {
+                        CONNECTION_COUNT.fetch_add(1, Ordering::Relaxed);
+                        if let Err(e) = handle_websocket_client(stream, ws_rx, HEARTBEAT).await {
                             log::error!("WebSocket client error: {}", e);
                         }
+                        // The receiver is dropped with the task, so fan-out stops here too
+                        CONNECTION_COUNT.fetch_sub(1, Ordering::Relaxed);
+                        log::debug!("WebSocket client {} disconnected", addr);
                     });
                 }
                 Err(e) => {
@@ -62,9 +107,73 @@ impl WebSocketBridge {
     }
 }
 
+/// Topics a WebSocket client asked for
+///
+/// Clients that never subscribe get every event, as before. After a
+/// `subscribe` they only get events matching one of their patterns
+/// (same `*` rules as `EventBus::subscribe_to`).
+#[derive(Debug, Default)]
+struct ClientSubscriptions {
+    topics: Option<BTreeSet<String>>,
+}
+
+impl ClientSubscriptions {
+    fn matches(&self, event_type: &str) -> bool {
+        match &self.topics {
+            None => true,
+            Some(topics) => topics.iter().any(|pattern| topic_matches(pattern, event_type)),
+        }
+    }
+
+    /// Apply a client message, returning the frame to send back
+    ///
+    /// `{"action":"subscribe","topics":["currency.*"]}` adds patterns and
+    /// `{"action":"unsubscribe","topics":[...]}` removes them. Unsubscribing
+    /// before any subscribe is a no-op, so the client keeps receiving every
+    /// event. Anything else gets an error frame; the connection stays open.
+    fn handle_message(&mut self, text: &str) -> Value {
+        let error = |message: &str| serde_json::json!({ "type": "error", "message": message });
+
+        let message: Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(_) => return error("Message is not valid JSON"),
+        };
+
+        let topics: Option<Vec<String>> = message.get("topics")
+            .and_then(|t| t.as_array())
+            .map(|topics| topics.iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect());
+
+        let action = message.get("action").and_then(|a| a.as_str());
+        let topics = match (action, topics) {
+            (Some("subscribe") | Some("unsubscribe"), Some(topics)) if !topics.is_empty() => topics,
+            (Some("subscribe") | Some("unsubscribe"), _) => return error("\"topics\" must be a non-empty array of strings"),
+            _ => return error("Unknown action, expected \"subscribe\" or \"unsubscribe\""),
+        };
+
+        let reply_type = if action == Some("subscribe") {
+            self.topics.get_or_insert_with(BTreeSet::new).extend(topics);
+            "subscribed"
+        } else {
+            if let Some(current) = &mut self.topics {
+                for topic in &topics {
+                    current.remove(topic);
+                }
+            }
+            "unsubscribed"
+        };
+
+        serde_json::json!({ "type": reply_type, "topics": self.topics })
+    }
+}
+
 async fn handle_websocket_client(
     stream: tokio::net::TcpStream,
-    mut ws_rx: broadcast::Receiver<String>,
+    mut ws_rx: broadcast::Receiver<Arc<WsEvent>>,
+    heartbeat: Heartbeat,
 ) -> Result<()> {
     let ws_stream = accept_async(stream).await?;
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
@@ -78,11 +187,30 @@ async fn handle_websocket_client(
 
     ws_sender.send(Message::Text(welcome_msg.to_string())).await?;
 
+    let mut subscriptions = ClientSubscriptions::default();
+
+    // Ping on an interval; a client that stays silent past interval + timeout is dropped
+    let mut ping_timer = tokio::time::interval_at(tokio::time::Instant::now() + heartbeat.interval, heartbeat.interval);
+    let mut last_seen = Instant::now();
+
     loop {
         tokio::select! {
-            // Forward plugin events to this WebSocket client
-            Ok(event_json) = ws_rx.recv() => {
-                if let Err(e) = ws_sender.send(Message::Text(event_json)).await {
+            _ = ping_timer.tick() => {
+                if last_seen.elapsed() > heartbeat.interval + heartbeat.timeout {
+                    log::debug!("WebSocket client stopped answering pings, closing");
+                    break;
+                }
+                if ws_sender.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+
+            // Forward plugin events this client subscribed to
+            Ok(event) = ws_rx.recv() => {
+                if !subscriptions.matches(&event.event_type) {
+                    continue;
+                }
+                if let Err(e) = ws_sender.send(Message::Text(event.json.clone())).await {
                     log::debug!("Failed to send to WebSocket client: {}", e);
                     break;
                 }
@@ -90,6 +218,9 @@ async fn handle_websocket_client(
 
             // Handle incoming WebSocket messages (mostly for ping/pong)
             msg = ws_receiver.next() => {
+                if let Some(Ok(_)) = &msg {
+                    last_seen = Instant::now();
+                }
                 match msg {
                     Some(Ok(Message::Close(_))) => {
                         log::debug!("WebSocket client closed connection");
@@ -99,8 +230,9 @@ async fn handle_websocket_client(
                         ws_sender.send(Message::Pong(data)).await?;
                     }
                     Some(Ok(Message::Text(text))) => {
-                        // Could handle client requests here if needed
                         log::debug!("WebSocket received: {}", text);
+                        let reply = subscriptions.handle_message(&text);
+                        ws_sender.send(Message::Text(reply.to_string())).await?;
                     }
                     Some(Err(e)) => {
                         log::error!("WebSocket error: {}", e);
@@ -118,3 +250,77 @@ async fn handle_websocket_client(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsubscribed_client_gets_everything() {
+        let subscriptions = ClientSubscriptions::default();
+        assert!(subscriptions.matches("packs.opened"));
+        assert!(subscriptions.matches("tts.spoken"));
+    }
+
+    #[test]
+    fn test_subscribe_filters_events() {
+        let mut subscriptions = ClientSubscriptions::default();
+        let reply = subscriptions.handle_message(r#"{"action":"subscribe","topics":["currency.*","packs.opened"]}"#);
+        assert_eq!(reply["type"], "subscribed");
+
+        assert!(subscriptions.matches("currency.spent"));
+        assert!(subscriptions.matches("packs.opened"));
+        assert!(!subscriptions.matches("tts.spoken"));
+
+        subscriptions.handle_message(r#"{"action":"unsubscribe","topics":["currency.*"]}"#);
+        assert!(!subscriptions.matches("currency.spent"));
+        assert!(subscriptions.matches("packs.opened"));
+    }
+
+    #[test]
+    fn test_unsubscribe_before_subscribe_keeps_everything() {
+        let mut subscriptions = ClientSubscriptions::default();
+        let reply = subscriptions.handle_message(r#"{"action":"unsubscribe","topics":["currency.*"]}"#);
+        assert_eq!(reply["type"], "unsubscribed");
+        assert!(reply["topics"].is_null());
+
+        assert_eq!(subscriptions.topics, None);
+        assert!(subscriptions.matches("currency.spent"));
+        assert!(subscriptions.matches("tts.spoken"));
+    }
+
+    #[test]
+    fn test_invalid_messages_get_error_frames() {
+        let mut subscriptions = ClientSubscriptions::default();
+        assert_eq!(subscriptions.handle_message("not json")["type"], "error");
+        assert_eq!(subscriptions.handle_message(r#"{"action":"subscribe"}"#)["type"], "error");
+        assert_eq!(subscriptions.handle_message(r#"{"action":"subscribe","topics":[]}"#)["type"], "error");
+        assert_eq!(subscriptions.handle_message(r#"{"action":"dance","topics":["x"]}"#)["type"], "error");
+
+        // Errors don't change what the client receives
+        assert!(subscriptions.matches("anything"));
+    }
+
+    #[tokio::test]
+    async fn test_client_that_ignores_pings_is_reaped() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (_ws_tx, ws_rx) = broadcast::channel::<Arc<WsEvent>>(8);
+
+        let heartbeat = Heartbeat {
+            interval: Duration::from_millis(50),
+            timeout: Duration::from_millis(50),
+        };
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            handle_websocket_client(stream, ws_rx, heartbeat).await
+        });
+
+        // Complete the handshake, then never read again (so never answer a ping)
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (_client, _) = tokio_tungstenite::client_async(format!("ws://{}/", addr), stream).await.unwrap();
+
+        let reaped = tokio::time::timeout(Duration::from_secs(2), server).await;
+        assert!(reaped.is_ok(), "silent client was not closed");
+    }
+}
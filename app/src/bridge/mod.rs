@@ -32,6 +32,96 @@ static DIST_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/dist");
 /// Global registry of loaded plugins
 pub static LOADED_PLUGINS: Lazy<Mutex<Vec<PluginInfo>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
+/// Max time a plugin handler may run before the bridge answers 504
+/// (PLUGIN_HANDLER_TIMEOUT_MS, default 30000)
+static PLUGIN_HANDLER_TIMEOUT: Lazy<std::time::Duration> = Lazy::new(|| {
+    let ms = env::var("PLUGIN_HANDLER_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(30_000);
+    std::time::Duration::from_millis(ms)
+});
+
+/// Default max request body a plugin route accepts before answering 413
+/// (MAX_BODY_BYTES, default 16 MB; routes can override with "max_body_bytes")
+static MAX_BODY_BYTES: Lazy<usize> = Lazy::new(|| {
+    env::var("MAX_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(16 * 1024 * 1024)
+});
+
+/// Request contexts or handler responses larger than this are logged as a warning
+/// (LARGE_PAYLOAD_WARN_BYTES, default 4 MB)
+static LARGE_PAYLOAD_WARN_BYTES: Lazy<u64> = Lazy::new(|| {
+    env::var("LARGE_PAYLOAD_WARN_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(4 * 1024 * 1024)
+});
+
+/// Bearer token every DLL plugin route and plugin admin endpoint requires (PLUGIN_API_TOKEN, unset = no auth)
+static PLUGIN_API_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
+    env::var("PLUGIN_API_TOKEN").ok().filter(|v| !v.trim().is_empty())
+});
+
+/// Requests per minute each DLL plugin's routes accept (PLUGIN_RATE_LIMIT_PER_MINUTE, unset = unlimited)
+static PLUGIN_RATE_LIMIT_PER_MINUTE: Lazy<Option<u32>> = Lazy::new(|| {
+    env::var("PLUGIN_RATE_LIMIT_PER_MINUTE")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|v| *v > 0)
+});
+
+/// Emit one JSON access-log line per API request (BRIDGE_ACCESS_LOG=1)
+static ACCESS_LOG: Lazy<bool> = Lazy::new(|| {
+    env::var("BRIDGE_ACCESS_LOG").map(|v| v == "1").unwrap_or(false)
+});
+
+/// Set once the app asks the bridge to shut down
+static SHUTDOWN: Lazy<tokio::sync::watch::Sender<bool>> = Lazy::new(|| tokio::sync::watch::channel(false).0);
+
+/// Set by run_server once plugins are stopped, so the app can wait for it
+static SHUTDOWN_COMPLETE: Lazy<(Mutex<bool>, std::sync::Condvar)> = Lazy::new(|| (Mutex::new(false), std::sync::Condvar::new()));
+
+/// Ask the bridge to stop accepting connections and stop its plugins
+pub fn request_shutdown() {
+    SHUTDOWN.send_replace(true);
+}
+
+/// Receiver that flips to `true` when shutdown is requested
+/// Background loops should select on it so they end with the bridge.
+pub fn shutdown_signal() -> tokio::sync::watch::Receiver<bool> {
+    SHUTDOWN.subscribe()
+}
+
+/// Block until run_server has finished shutting down, up to `timeout`
+/// Returns false if it didn't finish in time
+pub fn wait_for_shutdown(timeout: std::time::Duration) -> bool {
+    let (lock, condvar) = &*SHUTDOWN_COMPLETE;
+    let done = lock.lock().unwrap();
+    let (done, _) = condvar.wait_timeout_while(done, timeout, |done| !*done).unwrap();
+    *done
+}
+
+/// Resolves once shutdown has been requested
+async fn shutdown_requested() {
+    let mut shutdown = shutdown_signal();
+    let _ = shutdown.wait_for(|stop| *stop).await;
+}
+
+/// When the bridge started, for the uptime reported by /health
+static SERVER_STARTED: Lazy<std::time::Instant> = Lazy::new(std::time::Instant::now);
+
+/// Counter used to hand out short per-request ids for plugin calls
+static NEXT_REQUEST_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// Allocate a short request id (e.g. "00a3") used to tag plugin log lines
+pub fn next_request_id() -> String {
+    let id = NEXT_REQUEST_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("{:04x}", id & 0xffff)
+}
+
 /// Global assets root directory (set by plugins dynamically)
 pub static ASSETS_ROOT: Lazy<RwLock<PathBuf>> = Lazy::new(|| RwLock::new(PathBuf::new()));
 
@@ -65,46 +155,66 @@ pub fn get_config_json() -> Option<String> {
     std::fs::read_to_string(&config_path).ok()
 }
 
-/// Get the plugins directory based on environment
-/// - Development: {app}/plugins (built plugins in app folder)
-/// - Production: {exe_dir}/plugins (next to the executable)
-fn get_plugins_dir() -> PathBuf {
-    let exe_path = std::env::current_exe().ok();
-    let exe_dir = exe_path.as_ref()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+/// Get the plugins directory (see `resolve_plugins_dir` for the lookup order)
+pub(crate) fn get_plugins_dir() -> PathBuf {
+    let override_dir = env::var_os("WEBARCADE_PLUGINS_DIR")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from);
+    let exe_path = env::current_exe().ok();
 
-    // Check if we're running from target/release or target/debug
-    let in_target = exe_path.as_ref()
-        .and_then(|p| p.to_str())
-        .map(|s| s.contains("target\\debug") || s.contains("target/debug") || s.contains("target\\release") || s.contains("target/release"))
-        .unwrap_or(false);
-
-    if in_target {
-        // Running from app/target/release - use app/plugins
-        // Path: webarcade/app/target/release/webarcade.exe -> webarcade/app/plugins
-        if let Some(exe) = &exe_path {
-            if let Some(release_dir) = exe.parent() {        // release/
-                if let Some(target_dir) = release_dir.parent() { // target/
-                    if let Some(app_dir) = target_dir.parent() { // app/
-                        let plugins_dir = app_dir.join("plugins");
-                        if plugins_dir.exists() || std::fs::create_dir_all(&plugins_dir).is_ok() {
-                            log::info!("📁 Loading plugins from {:?}", plugins_dir);
-                            return plugins_dir;
-                        }
-                    }
-                }
-            }
-        }
+    let plugins_dir = resolve_plugins_dir(override_dir, exe_path.as_deref());
+    if !plugins_dir.exists() {
+        let _ = std::fs::create_dir_all(&plugins_dir);
     }
-
-    // Production: plugins folder next to executable
-    let plugins_dir = exe_dir.unwrap_or_default().join("plugins");
     log::info!("📁 Loading plugins from {:?}", plugins_dir);
     plugins_dir
 }
 
+/// Work out where plugins live, in order:
+/// 1. WEBARCADE_PLUGINS_DIR, if set
+/// 2. Development: {crate}/plugins, where {crate} is the nearest ancestor of
+///    the executable with a Cargo.toml (works for any profile or test binary)
+/// 3. {exe_dir}/plugins, if it exists (Windows/Linux installs)
+/// 4. {exe_dir}/../Resources/plugins, if it exists (macOS .app bundle)
+/// 5. {exe_dir}/plugins
+fn resolve_plugins_dir(override_dir: Option<PathBuf>, exe_path: Option<&std::path::Path>) -> PathBuf {
+    if let Some(dir) = override_dir {
+        return dir;
+    }
+
+    let exe_dir = exe_path.and_then(|p| p.parent()).map(|p| p.to_path_buf()).unwrap_or_default();
+
+    if let Some(crate_dir) = exe_dir.ancestors().find(|dir| dir.join("Cargo.toml").is_file()) {
+        return crate_dir.join("plugins");
+    }
+
+    let next_to_exe = exe_dir.join("plugins");
+    if next_to_exe.exists() {
+        return next_to_exe;
+    }
+
+    let resources = exe_dir.join("../Resources/plugins");
+    if resources.exists() {
+        return resources;
+    }
+
+    next_to_exe
+}
+
 /// Start the WebArcade bridge server
+/// Runs until `request_shutdown()` is called or the server fails
 pub async fn run_server() -> Result<()> {
+    let result = serve().await;
+
+    // Let wait_for_shutdown() return whether we stopped cleanly or failed
+    let (lock, condvar) = &*SHUTDOWN_COMPLETE;
+    *lock.lock().unwrap() = true;
+    condvar.notify_all();
+
+    result
+}
+
+async fn serve() -> Result<()> {
     // Initialize logger with timestamp (use try_init to avoid panic if already initialized)
     let _ = env_logger::Builder::from_default_env()
         .format_timestamp_secs()
@@ -116,15 +226,34 @@ pub async fn run_server() -> Result<()> {
     // Get configuration
     // Static files are served on port 3000 (FILE_PORT)
     // Bridge API is served on port 3001 (BRIDGE_PORT)
+    // All three bind to BRIDGE_HOST (default 127.0.0.1)
     let file_port = env::var("FILE_PORT").unwrap_or_else(|_| "3000".to_string());
     let bridge_port = env::var("BRIDGE_PORT").unwrap_or_else(|_| "3001".to_string());
     let ws_port = env::var("WS_PORT").unwrap_or_else(|_| "3002".to_string());
+    let bridge_host = env::var("BRIDGE_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+
+    // Fail before binding anything if the configuration is unusable
+    let host = parse_bind_host(&bridge_host)?;
+    let (file_port, bridge_port, ws_port) = validate_ports(&file_port, &bridge_port, &ws_port)?;
+
+    if !host.is_loopback() && crate::bridge::core::router_utils::cors_allows_any_origin() {
+        log::warn!(
+            "⚠️  Bridge is bound to {} with CORS * - any website can call it; set BRIDGE_CORS_ORIGINS to restrict origins",
+            host
+        );
+    }
 
     // Initialize core systems
     info!("📦 Initializing core systems...");
+    Lazy::force(&SERVER_STARTED);
 
     let event_bus = Arc::new(EventBus::new());
 
+    // Create service registry (SERVICE_TRACE=1 logs every call at debug level)
+    let service_registry = Arc::new(crate::bridge::core::services::ServiceRegistry::new());
+    service_registry.set_tracing(env::var("SERVICE_TRACE").map(|v| v == "1").unwrap_or(false));
+    crate::bridge::core::plugin_exports::set_global_service_registry(service_registry.clone());
+
     // Create router registry
     let router_registry = RouterRegistry::new();
 
@@ -149,317 +278,18 @@ pub async fn run_server() -> Result<()> {
 
             // Register dynamic plugin routes
             for plugin_info in &dynamic_plugins {
+                if let Some(error) = &plugin_info.error {
+                    error!("   - {} FAILED: {}", plugin_info.id, error);
+                    continue;
+                }
                 info!("   - {} (backend: {}, frontend: {})",
                     plugin_info.id,
                     plugin_info.has_backend,
                     plugin_info.has_frontend
                 );
 
-                // Register routes from routes.json
-                if !plugin_info.routes.is_empty() {
-                    info!("     └─ Registering {} routes", plugin_info.routes.len());
-
-                    // Create a router for this plugin
-                    let mut plugin_router = crate::bridge::core::PluginRouter::new();
-
-                    for route in &plugin_info.routes {
-                        if let (Some(method_str), Some(path), Some(handler_name)) = (
-                            route.get("method").and_then(|v| v.as_str()),
-                            route.get("path").and_then(|v| v.as_str()),
-                            route.get("handler").and_then(|v| v.as_str()),
-                        ) {
-                            // Parse HTTP method
-                            let method = match method_str {
-                                "GET" => hyper::Method::GET,
-                                "POST" => hyper::Method::POST,
-                                "PUT" => hyper::Method::PUT,
-                                "DELETE" => hyper::Method::DELETE,
-                                "PATCH" => hyper::Method::PATCH,
-                                _ => {
-                                    error!("Unknown HTTP method: {}", method_str);
-                                    continue;
-                                }
-                            };
-
-                            let plugin_id = plugin_info.id.clone();
-                            let handler_name_owned = handler_name.to_string();
-
-                            // Clone route_path for path parameter extraction
-                            let route_pattern = path.to_string();
-
-                            // Create a handler that will call the DLL function
-                            plugin_router.route(method, path, move |path_arg, query, req| {
-                                let plugin_id = plugin_id.clone();
-                                let handler_name = handler_name_owned.clone();
-                                let route_pattern = route_pattern.clone();
-
-                                Box::pin(async move {
-                                    use http_body_util::Full;
-                                    use hyper::body::Bytes;
-                                    use http_body_util::combinators::BoxBody;
-                                    use http_body_util::BodyExt;
-                                    use std::collections::HashMap;
-
-                                    // Extract method before consuming request
-                                    let method_str = req.method().to_string();
-
-                                    // Extract headers before consuming request
-                                    let mut headers_map: HashMap<String, String> = HashMap::new();
-                                    for (key, value) in req.headers().iter() {
-                                        if let Ok(v) = value.to_str() {
-                                            headers_map.insert(key.to_string(), v.to_string());
-                                        }
-                                    }
-
-                                    // Collect the request body
-                                    let body_bytes = match req.collect().await {
-                                        Ok(collected) => collected.to_bytes(),
-                                        Err(e) => {
-                                            let error_json = serde_json::json!({
-                                                "error": format!("Failed to read request body: {}", e)
-                                            }).to_string();
-                                            return hyper::Response::builder()
-                                                .status(400)
-                                                .header("Content-Type", "application/json")
-                                                .header("Access-Control-Allow-Origin", "*")
-                                                .body(BoxBody::new(Full::new(Bytes::from(error_json))))
-                                                .unwrap();
-                                        }
-                                    };
-
-                                    // Parse query string into key-value pairs
-                                    let query_params: HashMap<String, String> = query
-                                        .split('&')
-                                        .filter(|s| !s.is_empty())
-                                        .filter_map(|pair| {
-                                            let mut parts = pair.splitn(2, '=');
-                                            match (parts.next(), parts.next()) {
-                                                (Some(k), Some(v)) => Some((
-                                                    urlencoding::decode(k).unwrap_or_default().into_owned(),
-                                                    urlencoding::decode(v).unwrap_or_default().into_owned()
-                                                )),
-                                                (Some(k), None) => Some((
-                                                    urlencoding::decode(k).unwrap_or_default().into_owned(),
-                                                    String::new()
-                                                )),
-                                                _ => None
-                                            }
-                                        })
-                                        .collect();
-
-                                    // Extract path parameters (e.g., /user/:id -> {"id": "123"})
-                                    let path_params: HashMap<String, String> = {
-                                        let pattern_parts: Vec<&str> = route_pattern.split('/').collect();
-                                        let path_parts: Vec<&str> = path_arg.split('/').collect();
-                                        let mut params = HashMap::new();
-
-                                        if pattern_parts.len() == path_parts.len() {
-                                            for (pattern_part, path_part) in pattern_parts.iter().zip(path_parts.iter()) {
-                                                if pattern_part.starts_with(':') {
-                                                    let param_name = &pattern_part[1..];
-                                                    params.insert(param_name.to_string(), path_part.to_string());
-                                                }
-                                            }
-                                        }
-                                        params
-                                    };
-
-                                    // Build full HTTP context as JSON
-                                    let request_context = serde_json::json!({
-                                        "method": method_str,
-                                        "path": path_arg,
-                                        "query": query_params,
-                                        "path_params": path_params,
-                                        "headers": headers_map,
-                                        "body": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &body_bytes),
-                                        "body_len": body_bytes.len()
-                                    });
-
-                                    // Log request being sent to DLL (for debugging)
-                                    log::debug!("[Bridge->DLL] {} {} (body_len: {} bytes)", method_str, path_arg, body_bytes.len());
-                                    if headers_map.get("content-type").map(|ct| ct.contains("multipart")).unwrap_or(false) {
-                                        log::info!("[Bridge->DLL] Multipart request: body_len={}, first 20 bytes: {:?}",
-                                            body_bytes.len(),
-                                            &body_bytes[..std::cmp::min(20, body_bytes.len())]
-                                        );
-                                    }
-
-                                    let request_json = match serde_json::to_string(&request_context) {
-                                        Ok(json) => json,
-                                        Err(e) => {
-                                            let error_json = serde_json::json!({
-                                                "error": format!("Failed to serialize request context: {}", e)
-                                            }).to_string();
-                                            return hyper::Response::builder()
-                                                .status(500)
-                                                .header("Content-Type", "application/json")
-                                                .header("Access-Control-Allow-Origin", "*")
-                                                .body(BoxBody::new(Full::new(Bytes::from(error_json))))
-                                                .unwrap();
-                                        }
-                                    };
-
-                                    // Look up the plugin library
-                                    let lib = {
-                                        let libs = crate::bridge::core::plugin_exports::PLUGIN_LIBRARIES.lock().unwrap();
-                                        libs.get(&plugin_id).cloned()
-                                    };
-
-                                    if let Some(lib) = lib {
-                                        // The DLL handler creates its own runtime internally,
-                                        // so we just pass a null pointer for the runtime_ptr parameter
-                                        let runtime_ptr: *const () = std::ptr::null();
-
-                                        // New handler signature: extern "C" fn(*const u8, usize, *const ()) -> *const u8
-                                        // Args: request_json_ptr, request_json_len, runtime_ptr -> response_json_ptr
-                                        let result: Result<libloading::Symbol<extern "C" fn(*const u8, usize, *const ()) -> *const u8>, _> = unsafe {
-                                            lib.get(handler_name.as_bytes())
-                                        };
-
-                                        let response_json_str = match result {
-                                            Ok(handler_fn) => {
-                                                // Call the handler with full HTTP context
-                                                let ptr = handler_fn(request_json.as_ptr(), request_json.len(), runtime_ptr);
-                                                if ptr.is_null() {
-                                                    let error_json = serde_json::json!({
-                                                        "error": "Handler returned null"
-                                                    }).to_string();
-
-                                                    return hyper::Response::builder()
-                                                        .status(500)
-                                                        .header("Content-Type", "application/json")
-                                                        .header("Access-Control-Allow-Origin", "*")
-                                                        .body(BoxBody::new(Full::new(Bytes::from(error_json))))
-                                                        .unwrap();
-                                                }
-
-                                                // Read the response JSON string from the pointer
-                                                let response_str = unsafe {
-                                                    let c_str = std::ffi::CStr::from_ptr(ptr as *const i8);
-                                                    c_str.to_string_lossy().into_owned()
-                                                };
-
-                                                // Free the string (if the plugin exports free_string)
-                                                let free_result: Result<libloading::Symbol<extern "C" fn(*mut u8)>, _> = unsafe {
-                                                    lib.get(b"free_string")
-                                                };
-                                                if let Ok(free_fn) = free_result {
-                                                    free_fn(ptr as *mut u8);
-                                                }
-
-                                                response_str
-                                            }
-                                            Err(e) => {
-                                                let error_json = serde_json::json!({
-                                                    "error": format!("Handler function '{}' not found: {}", handler_name, e)
-                                                }).to_string();
-
-                                                return hyper::Response::builder()
-                                                    .status(500)
-                                                    .header("Content-Type", "application/json")
-                                                    .header("Access-Control-Allow-Origin", "*")
-                                                    .body(BoxBody::new(Full::new(Bytes::from(error_json))))
-                                                    .unwrap();
-                                            }
-                                        };
-
-                                        // Parse the response JSON to extract status, headers, and body
-                                        let response_data: serde_json::Value = match serde_json::from_str(&response_json_str) {
-                                            Ok(v) => v,
-                                            Err(_) => {
-                                                // If parsing fails, treat the whole string as JSON body (legacy behavior)
-                                                return hyper::Response::builder()
-                                                    .status(200)
-                                                    .header("Content-Type", "application/json")
-                                                    .header("Access-Control-Allow-Origin", "*")
-                                                    .body(BoxBody::new(Full::new(Bytes::from(response_json_str))))
-                                                    .unwrap();
-                                            }
-                                        };
-
-                                        // Check if response uses new format with status/headers/body
-                                        if response_data.get("__ffi_response__").is_some() {
-                                            let status = response_data.get("status")
-                                                .and_then(|v| v.as_u64())
-                                                .unwrap_or(200) as u16;
-
-                                            let mut builder = hyper::Response::builder().status(status);
-
-                                            // Check if custom headers already include CORS
-                                            let mut has_cors = false;
-
-                                            // Add custom headers
-                                            if let Some(headers) = response_data.get("headers").and_then(|v| v.as_object()) {
-                                                for (key, value) in headers {
-                                                    if let Some(v) = value.as_str() {
-                                                        if key.to_lowercase() == "access-control-allow-origin" {
-                                                            has_cors = true;
-                                                        }
-                                                        builder = builder.header(key.as_str(), v);
-                                                    }
-                                                }
-                                            }
-
-                                            // Only add CORS header if not already present
-                                            if !has_cors {
-                                                builder = builder.header("Access-Control-Allow-Origin", "*");
-                                            }
-
-                                            // Handle body - check if it's base64 encoded binary
-                                            let body_bytes = if response_data.get("body_base64").is_some() {
-                                                // Binary body encoded as base64
-                                                let b64 = response_data.get("body_base64")
-                                                    .and_then(|v| v.as_str())
-                                                    .unwrap_or("");
-                                                base64::Engine::decode(&base64::engine::general_purpose::STANDARD, b64)
-                                                    .unwrap_or_default()
-                                            } else if let Some(body_str) = response_data.get("body").and_then(|v| v.as_str()) {
-                                                // String body
-                                                body_str.as_bytes().to_vec()
-                                            } else if let Some(body_obj) = response_data.get("body") {
-                                                // JSON object body
-                                                serde_json::to_string(body_obj)
-                                                    .unwrap_or_default()
-                                                    .into_bytes()
-                                            } else {
-                                                Vec::new()
-                                            };
-
-                                            builder
-                                                .body(BoxBody::new(Full::new(Bytes::from(body_bytes))))
-                                                .unwrap()
-                                        } else {
-                                            // Legacy format - treat entire response as JSON body
-                                            hyper::Response::builder()
-                                                .status(200)
-                                                .header("Content-Type", "application/json")
-                                                .header("Access-Control-Allow-Origin", "*")
-                                                .body(BoxBody::new(Full::new(Bytes::from(response_json_str))))
-                                                .unwrap()
-                                        }
-                                    } else {
-                                        let error_json = serde_json::json!({
-                                            "error": format!("Plugin library not found: {}", plugin_id)
-                                        }).to_string();
-
-                                        hyper::Response::builder()
-                                            .status(500)
-                                            .header("Content-Type", "application/json")
-                                            .header("Access-Control-Allow-Origin", "*")
-                                            .body(BoxBody::new(Full::new(Bytes::from(error_json))))
-                                            .unwrap()
-                                    }
-                                })
-                            });
-
-                            info!("       {} {} -> {}", method_str, path, handler_name);
-                        }
-                    }
-
-                    // Register the router (synchronously to avoid race condition)
-                    let plugin_id = plugin_info.id.clone();
-                    router_registry.register(plugin_id, plugin_router).await;
-                }
+                // Register routes from the plugin manifest
+                register_plugin_routes(&router_registry, plugin_info).await;
             }
         }
         Err(e) => {
@@ -471,27 +301,41 @@ pub async fn run_server() -> Result<()> {
 
     // Start WebSocket server for real-time events
     let event_bus_ws = event_bus.clone();
-    let ws_port_clone = ws_port.clone();
     tokio::spawn(async move {
         let ws_bridge = WebSocketBridge::new(event_bus_ws);
-        if let Err(e) = ws_bridge.start(ws_port_clone).await {
+        if let Err(e) = ws_bridge.start(SocketAddr::new(host, ws_port), shutdown_signal()).await {
             error!("WebSocket server error: {}", e);
         }
     });
 
+    // Fire schedules registered with ctx.schedule() until the bridge shuts down
+    // (one scheduler per bridge, so each schedule fires once)
+    let scheduler = Arc::new(crate::bridge::core::scheduler::Scheduler::new(
+        event_bus.clone(),
+        crate::bridge::core::database::default_database_path(),
+    ));
+    tokio::spawn(scheduler.run(shutdown_signal()));
+
     // Start static file server on port 3000
-    let file_addr: SocketAddr = format!("127.0.0.1:{}", file_port).parse()?;
+    let file_addr = SocketAddr::new(host, file_port);
     let file_listener = TcpListener::bind(file_addr).await?;
     info!("📁 Static file server listening on http://{}", file_addr);
 
     tokio::spawn(async move {
         loop {
-            match file_listener.accept().await {
+            let accepted = tokio::select! {
+                accepted = file_listener.accept() => accepted,
+                _ = shutdown_requested() => break,
+            };
+            match accepted {
                 Ok((stream, _)) => {
                     let io = TokioIo::new(stream);
                     tokio::task::spawn(async move {
                         let service = service_fn(|req| async move {
-                            Ok::<_, std::convert::Infallible>(handle_static_request(req).await)
+                            let origin = request_origin(&req);
+                            let mut response = handle_static_request(req).await;
+                            crate::bridge::core::router_utils::apply_cors(origin.as_deref(), &mut response);
+                            Ok::<_, std::convert::Infallible>(response)
                         });
 
                         let conn = http1::Builder::new()
@@ -511,25 +355,45 @@ pub async fn run_server() -> Result<()> {
     });
 
     // Start Bridge API server on port 3001
-    let bridge_addr: SocketAddr = format!("127.0.0.1:{}", bridge_port).parse()?;
+    let bridge_addr = SocketAddr::new(host, bridge_port);
     let bridge_listener = TcpListener::bind(bridge_addr).await?;
 
     info!("🌐 Bridge API server listening on http://{}", bridge_addr);
-    info!("📡 WebSocket server listening on ws://127.0.0.1:{}", ws_port);
+    info!("📡 WebSocket server listening on ws://{}", SocketAddr::new(host, ws_port));
     info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     info!("✨ WebArcade Bridge is ready!");
     info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     loop {
-        let (stream, _) = bridge_listener.accept().await?;
+        let (stream, peer_addr) = tokio::select! {
+            accepted = bridge_listener.accept() => accepted?,
+            _ = shutdown_requested() => break,
+        };
         let io = TokioIo::new(stream);
         let router_registry = router_registry.clone_registry();
 
         tokio::task::spawn(async move {
-            let service = service_fn(move |req| {
+            let service = service_fn(move |mut req: Request<Incoming>| {
                 let router = router_registry.clone_registry();
+                // Client address, for per-IP rate limiting
+                req.extensions_mut().insert(peer_addr);
                 async move {
-                    Ok::<_, std::convert::Infallible>(handle_api_request(req, router).await)
+                    let origin = request_origin(&req);
+                    let started = std::time::Instant::now();
+                    let method = req.method().to_string();
+                    let path = req.uri().path().to_string();
+                    let plugin = metrics_plugin_label(&path, &router).await;
+
+                    let mut response = handle_api_request(req, router).await;
+                    crate::bridge::core::router_utils::apply_cors(origin.as_deref(), &mut response);
+
+                    let status = response.status().as_u16();
+                    crate::bridge::core::metrics::record_request(&plugin, &method, status, started.elapsed());
+                    if *ACCESS_LOG {
+                        let body_bytes = hyper::body::Body::size_hint(response.body()).exact();
+                        log::info!(target: "bridge::access", "{}", access_log_line(&method, &path, &plugin, status, started.elapsed(), body_bytes));
+                    }
+                    Ok::<_, std::convert::Infallible>(response)
                 }
             });
 
@@ -542,6 +406,429 @@ pub async fn run_server() -> Result<()> {
             }
         });
     }
+
+    info!("🛑 Shutting down bridge...");
+    stop_dynamic_plugins().await;
+
+    info!("🛑 Bridge stopped");
+    Ok(())
+}
+
+/// Call the optional `plugin_stop` export of every loaded plugin DLL
+async fn stop_dynamic_plugins() {
+    let libraries: Vec<(String, Arc<libloading::Library>)> = crate::bridge::core::plugin_exports::PLUGIN_LIBRARIES
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(id, lib)| (id.clone(), lib.clone()))
+        .collect();
+
+    for (plugin_id, lib) in libraries {
+        stop_plugin_library(&plugin_id, lib).await;
+    }
+}
+
+/// Call a plugin DLL's optional `plugin_stop` export
+/// Plugins without it are left alone; a panicking stop is logged and skipped.
+async fn stop_plugin_library(plugin_id: &str, lib: Arc<libloading::Library>) {
+    type StopFn = unsafe extern "C" fn();
+
+    let stopped = tokio::task::spawn_blocking(move || unsafe {
+        match lib.get::<StopFn>(b"plugin_stop") {
+            Ok(stop) => {
+                stop();
+                true
+            }
+            Err(_) => false,
+        }
+    }).await;
+
+    match stopped {
+        Ok(true) => info!("   - stopped {}", plugin_id),
+        Ok(false) => {}
+        Err(e) if e.is_panic() => error!("Plugin {} panicked while stopping: {}", plugin_id, panic_message(e.into_panic())),
+        Err(e) => error!("Failed to stop plugin {}: {}", plugin_id, e),
+    }
+}
+
+/// The request's Origin header, if any
+fn request_origin<B>(req: &Request<B>) -> Option<String> {
+    req.headers()
+        .get(hyper::header::ORIGIN)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// Parse BRIDGE_HOST into an IP address to bind to
+fn parse_bind_host(host: &str) -> Result<std::net::IpAddr> {
+    host.trim().parse()
+        .map_err(|_| anyhow::anyhow!("BRIDGE_HOST '{}' is not a valid IP address", host))
+}
+
+/// Parse FILE_PORT, BRIDGE_PORT and WS_PORT, requiring three distinct ports in 1-65535
+fn validate_ports(file_port: &str, bridge_port: &str, ws_port: &str) -> Result<(u16, u16, u16)> {
+    let parse = |name: &str, value: &str| -> Result<u16> {
+        match value.trim().parse::<u16>() {
+            Ok(0) | Err(_) => Err(anyhow::anyhow!("{} '{}' is not a port between 1 and 65535", name, value)),
+            Ok(port) => Ok(port),
+        }
+    };
+
+    let file = parse("FILE_PORT", file_port)?;
+    let bridge = parse("BRIDGE_PORT", bridge_port)?;
+    let ws = parse("WS_PORT", ws_port)?;
+
+    if file == bridge || file == ws || bridge == ws {
+        return Err(anyhow::anyhow!(
+            "FILE_PORT ({}), BRIDGE_PORT ({}) and WS_PORT ({}) must all be different",
+            file, bridge, ws
+        ));
+    }
+
+    Ok((file, bridge, ws))
+}
+
+/// Router for a DLL plugin with the bridge-level middleware applied
+/// (bearer token first, so unauthenticated calls don't use up the rate limit)
+fn dll_plugin_router(plugin_id: &str, api_token: Option<String>, per_minute: Option<u32>) -> crate::bridge::core::PluginRouter {
+    let mut router = crate::bridge::core::PluginRouter::new();
+    if let Some(token) = api_token {
+        router = router.with_middleware(crate::bridge::core::bearer_token_auth(token));
+    }
+    if let Some(per_minute) = per_minute {
+        router = router.with_middleware(crate::bridge::core::rate_limit_requests(format!("routes:{}", plugin_id), per_minute));
+    }
+    router
+}
+
+/// Register a dynamic plugin's routes (from its manifest) with the router registry.
+/// Each route forwards to the handler of the same name exported by the plugin's DLL.
+async fn register_plugin_routes(router_registry: &RouterRegistry, plugin_info: &PluginInfo) {
+    // Failed plugins get no routes, so requests 404 instead of 500ing per call
+    if !plugin_info.is_loaded() || plugin_info.routes.is_empty() {
+        return;
+    }
+
+    info!("     └─ Registering {} routes", plugin_info.routes.len());
+
+    // Create a router for this plugin
+    let mut plugin_router = dll_plugin_router(&plugin_info.id, PLUGIN_API_TOKEN.clone(), *PLUGIN_RATE_LIMIT_PER_MINUTE);
+
+    for route in &plugin_info.routes {
+        if let (Some(method_str), Some(path), Some(handler_name)) = (
+            route.get("method").and_then(|v| v.as_str()),
+            route.get("path").and_then(|v| v.as_str()),
+            route.get("handler").and_then(|v| v.as_str()),
+        ) {
+            // Parse HTTP method
+            let method = match method_str {
+                "GET" => hyper::Method::GET,
+                "POST" => hyper::Method::POST,
+                "PUT" => hyper::Method::PUT,
+                "DELETE" => hyper::Method::DELETE,
+                "PATCH" => hyper::Method::PATCH,
+                _ => {
+                    error!("Unknown HTTP method: {}", method_str);
+                    continue;
+                }
+            };
+
+            let plugin_id = plugin_info.id.clone();
+            let handler_name_owned = handler_name.to_string();
+
+            // Per-route body limit (e.g. uploads), falling back to MAX_BODY_BYTES
+            let max_body_bytes = route.get("max_body_bytes")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(*MAX_BODY_BYTES);
+
+            // Large-upload routes get the body written to a temp file instead of
+            // base64 in the request context ("stream_body": true)
+            let stream_body = route.get("stream_body")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            // Clone route_path for path parameter extraction
+            let route_pattern = path.to_string();
+
+            // Create a handler that will call the DLL function
+            plugin_router.route_named(method, path, handler_name, move |path_arg, query, req| {
+                let plugin_id = plugin_id.clone();
+                let handler_name = handler_name_owned.clone();
+                let route_pattern = route_pattern.clone();
+
+                Box::pin(async move {
+                    use http_body_util::Full;
+                    use hyper::body::Bytes;
+                    use http_body_util::combinators::BoxBody;
+                    use std::collections::HashMap;
+
+                    // Extract method before consuming request
+                    let request_method = req.method().clone();
+                    let method_str = request_method.to_string();
+
+                    // Tag for log lines from this request: [plugin_id#reqid]
+                    let request_id = next_request_id();
+                    let log_tag = format!("{}#{}", plugin_id, request_id);
+
+                    // Extract headers before consuming request
+                    // headers_map keeps the first value per name for existing handlers,
+                    // header_list keeps every value in order (e.g. repeated Cookie lines)
+                    let mut headers_map: HashMap<String, String> = HashMap::new();
+                    let mut header_list: Vec<(String, String)> = Vec::new();
+                    for (key, value) in req.headers().iter() {
+                        if let Ok(v) = value.to_str() {
+                            headers_map.entry(key.to_string()).or_insert_with(|| v.to_string());
+                            header_list.push((key.to_string(), v.to_string()));
+                        }
+                    }
+
+                    // Kept for ranging the plugin's reply (headers_map moves into the context)
+                    let range_header = headers_map.get("range").cloned();
+
+                    // Collect the request body (413 past the route's limit)
+                    // Streaming routes pass the handler a temp file path instead of the bytes
+                    let body = if stream_body {
+                        let path = upload_temp_path();
+                        stream_body_to_file(req, max_body_bytes, &path).await
+                            .map(|len| (Bytes::new(), len, Some(path)))
+                    } else {
+                        read_body_limited(req, max_body_bytes).await
+                            .map(|bytes| { let len = bytes.len() as u64; (bytes, len, None) })
+                    };
+                    let (body_bytes, body_len, body_path) = match body {
+                        Ok(body) => body,
+                        Err(response) => {
+                            log::warn!("[{}] Rejected request body: {}", log_tag, response.status());
+                            return response;
+                        }
+                    };
+                    // Deletes the temp file once the handler is done with it
+                    let upload = TempUpload(body_path.clone());
+
+                    // What the body really is, for handlers that store or serve it
+                    let body_content_type = upload_content_type(&body_bytes, body_path.as_deref(), headers_map.get("content-type").map(String::as_str));
+
+                    // Parse query string into key-value pairs
+                    let query_params: HashMap<String, String> = query
+                        .split('&')
+                        .filter(|s| !s.is_empty())
+                        .filter_map(|pair| {
+                            let mut parts = pair.splitn(2, '=');
+                            match (parts.next(), parts.next()) {
+                                (Some(k), Some(v)) => Some((
+                                    urlencoding::decode(k).unwrap_or_default().into_owned(),
+                                    urlencoding::decode(v).unwrap_or_default().into_owned()
+                                )),
+                                (Some(k), None) => Some((
+                                    urlencoding::decode(k).unwrap_or_default().into_owned(),
+                                    String::new()
+                                )),
+                                _ => None
+                            }
+                        })
+                        .collect();
+
+                    // Extract path parameters (e.g., /user/:id -> {"id": "123"}, /files/*path -> {"path": "a/b.txt"})
+                    let path_params: HashMap<String, String> = crate::bridge::core::plugin_router::match_route(&route_pattern, &path_arg)
+                        .unwrap_or_default();
+
+                    // Build full HTTP context as JSON
+                    let request_context = serde_json::json!({
+                        "method": method_str,
+                        "path": path_arg,
+                        "query": query_params,
+                        "path_params": path_params,
+                        "headers": headers_map,
+                        "header_list": header_list,
+                        "body": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &body_bytes),
+                        "body_len": body_len,
+                        "body_path": body_path.as_ref().map(|p| p.to_string_lossy().into_owned()),
+                        "body_content_type": body_content_type,
+                        "plugin_id": plugin_id,
+                        "request_id": request_id,
+                        "db_path": crate::bridge::core::database::default_database_path()
+                    });
+
+                    // Log request being sent to DLL (for debugging)
+                    log::debug!("[{}] [Bridge->DLL] {} {} (body_len: {} bytes)", log_tag, method_str, path_arg, body_len);
+                    if headers_map.get("content-type").map(|ct| ct.contains("multipart")).unwrap_or(false) {
+                        log::info!("[{}] [Bridge->DLL] Multipart request: body_len={}, first 20 bytes: {:?}",
+                            log_tag,
+                            body_len,
+                            &body_bytes[..std::cmp::min(20, body_bytes.len())]
+                        );
+                    }
+
+                    let request_json = match serde_json::to_string(&request_context) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            let error_json = serde_json::json!({
+                                "error": format!("Failed to serialize request context: {}", e)
+                            }).to_string();
+                            return hyper::Response::builder()
+                                .status(500)
+                                .header("Content-Type", "application/json")
+                                .body(BoxBody::new(Full::new(Bytes::from(error_json))))
+                                .unwrap();
+                        }
+                    };
+
+                    crate::bridge::core::metrics::record_payload_size(&plugin_id, &route_pattern, "request", body_len);
+                    crate::bridge::core::metrics::record_payload_size(&plugin_id, &route_pattern, "context", request_json.len() as u64);
+                    if let Some(warning) = large_payload_warning("Request context", request_json.len() as u64, *LARGE_PAYLOAD_WARN_BYTES) {
+                        log::warn!("[{}] {} {} {}; mark the route \"stream_body\": true to pass the body as a file", log_tag, method_str, route_pattern, warning);
+                    }
+
+                    // Clone the library handle so the lock isn't held during the DLL call
+                    let lib = crate::bridge::core::plugin_exports::get_plugin_library(&plugin_id);
+
+                    if let Some(lib) = lib {
+                        // Run the DLL call on a blocking thread so a slow handler can't stall
+                        // the async workers, and answer 504 if it exceeds the timeout.
+                        // A timed-out handler keeps running on its blocking thread - the
+                        // DLL call can't be cancelled, only abandoned.
+                        let call_lib = lib.clone();
+                        let call_handler_name = handler_name.clone();
+                        let call = tokio::task::spawn_blocking(move || {
+                            let _upload = upload;
+                            call_plugin_handler(&call_lib, &call_handler_name, &request_json)
+                        });
+
+                        let response_json_str = match tokio::time::timeout(*PLUGIN_HANDLER_TIMEOUT, call).await {
+                            Ok(Ok(Ok(response_str))) => response_str,
+                            Ok(Ok(Err(message))) => {
+                                let error_json = serde_json::json!({
+                                    "error": message
+                                }).to_string();
+
+                                return hyper::Response::builder()
+                                    .status(500)
+                                    .header("Content-Type", "application/json")
+                                    .body(BoxBody::new(Full::new(Bytes::from(error_json))))
+                                    .unwrap();
+                            }
+                            Ok(Err(e)) => {
+                                // A panic only unwinds to here in debug builds (see panic_message)
+                                let reason = if e.is_panic() {
+                                    format!("panicked: {}", panic_message(e.into_panic()))
+                                } else {
+                                    e.to_string()
+                                };
+                                log::error!("[{}] Handler '{}' failed: {}", log_tag, handler_name, reason);
+                                let error_json = serde_json::json!({
+                                    "error": format!("Handler '{}' failed: {}", handler_name, reason)
+                                }).to_string();
+
+                                return hyper::Response::builder()
+                                    .status(500)
+                                    .header("Content-Type", "application/json")
+                                    .body(BoxBody::new(Full::new(Bytes::from(error_json))))
+                                    .unwrap();
+                            }
+                            Err(_) => {
+                                log::warn!("[{}] Handler '{}' timed out after {:?}", log_tag, handler_name, *PLUGIN_HANDLER_TIMEOUT);
+                                let error_json = serde_json::json!({
+                                    "error": format!("Handler '{}' timed out after {} ms", handler_name, PLUGIN_HANDLER_TIMEOUT.as_millis())
+                                }).to_string();
+
+                                return hyper::Response::builder()
+                                    .status(504)
+                                    .header("Content-Type", "application/json")
+                                    .body(BoxBody::new(Full::new(Bytes::from(error_json))))
+                                    .unwrap();
+                            }
+                        };
+
+                        crate::bridge::core::metrics::record_payload_size(&plugin_id, &route_pattern, "response", response_json_str.len() as u64);
+                        if let Some(warning) = large_payload_warning("Response", response_json_str.len() as u64, *LARGE_PAYLOAD_WARN_BYTES) {
+                            log::warn!("[{}] {} {} {}; return a stream_id to stream the body in chunks", log_tag, method_str, route_pattern, warning);
+                        }
+
+                        // Parse the response JSON to extract status, headers, and body
+                        let response_data: serde_json::Value = match serde_json::from_str(&response_json_str) {
+                            Ok(v) => v,
+                            Err(_) => {
+                                // If parsing fails, treat the whole string as JSON body (legacy behavior)
+                                return hyper::Response::builder()
+                                    .status(200)
+                                    .header("Content-Type", "application/json")
+                                    .body(BoxBody::new(Full::new(Bytes::from(response_json_str))))
+                                    .unwrap();
+                            }
+                        };
+
+                        // Check if response uses new format with status/headers/body
+                        if response_data.get("__ffi_response__").is_some() {
+                            let status = response_data.get("status")
+                                .and_then(|v| v.as_u64())
+                                .unwrap_or(200) as u16;
+
+                            // Add custom headers (repeated headers like Set-Cookie are preserved)
+                            let builder = apply_ffi_headers(
+                                hyper::Response::builder().status(status),
+                                response_data.get("headers"),
+                            );
+
+                            // Streaming body - pull chunks from the plugin instead of one buffer
+                            // Falls through to the whole-body path if the plugin has no next_chunk export
+                            if let Some(stream_id) = response_data.get("stream_id").and_then(|v| v.as_u64()) {
+                                if let Some(body) = stream_plugin_body(lib.clone(), stream_id) {
+                                    return builder.body(body).unwrap();
+                                }
+                                log::warn!("[Bridge] Plugin {} returned a stream but does not export next_chunk", plugin_id);
+                            }
+
+                            // Handle body - check if it's base64 encoded binary
+                            let body_bytes = if response_data.get("body_base64").is_some() {
+                                // Binary body encoded as base64
+                                let b64 = response_data.get("body_base64")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or("");
+                                base64::Engine::decode(&base64::engine::general_purpose::STANDARD, b64)
+                                    .unwrap_or_default()
+                            } else if let Some(body_str) = response_data.get("body").and_then(|v| v.as_str()) {
+                                // String body
+                                body_str.as_bytes().to_vec()
+                            } else if let Some(body_obj) = response_data.get("body") {
+                                // JSON object body
+                                serde_json::to_string(body_obj)
+                                    .unwrap_or_default()
+                                    .into_bytes()
+                            } else {
+                                Vec::new()
+                            };
+
+                            ffi_body_response(builder, &request_method, status, Bytes::from(body_bytes), range_header.as_deref())
+                        } else {
+                            // Legacy format - treat entire response as JSON body
+                            hyper::Response::builder()
+                                .status(200)
+                                .header("Content-Type", "application/json")
+                                .body(BoxBody::new(Full::new(Bytes::from(response_json_str))))
+                                .unwrap()
+                        }
+                    } else {
+                        let error_json = serde_json::json!({
+                            "error": format!("Plugin library not found: {}", plugin_id)
+                        }).to_string();
+
+                        hyper::Response::builder()
+                            .status(500)
+                            .header("Content-Type", "application/json")
+                            .body(BoxBody::new(Full::new(Bytes::from(error_json))))
+                            .unwrap()
+                    }
+                })
+            });
+
+            info!("       {} {} -> {}", method_str, path, handler_name);
+        }
+    }
+
+    // Register the router (synchronously to avoid race condition)
+    let plugin_id = plugin_info.id.clone();
+    router_registry.register(plugin_id, plugin_router).await;
 }
 
 /// Check if we're running in development mode (from target/ directory)
@@ -569,7 +856,7 @@ fn get_dist_dir() -> Option<PathBuf> {
 }
 
 /// Serve a static file - from disk in dev mode, from embedded in production
-fn serve_static_file(path: &str) -> Option<Response<BoxBody<Bytes, Infallible>>> {
+fn serve_static_file(path: &str, accept_gzip: bool, range: Option<&str>) -> Option<Response<BoxBody<Bytes, Infallible>>> {
     // Normalize path - default to index.html for root
     let file_path = if path == "/" || path.is_empty() {
         "index.html"
@@ -610,15 +897,31 @@ fn serve_static_file(path: &str) -> Option<Response<BoxBody<Bytes, Infallible>>>
                     _ => "application/octet-stream",
                 };
 
-                // In dev mode, never cache anything
-                return Some(Response::builder()
+                // In dev mode, never cache anything (compressed on every request)
+                let mut builder = Response::builder()
                     .status(StatusCode::OK)
                     .header("Content-Type", content_type)
-                    .header("Access-Control-Allow-Origin", "*")
                     .header("Cache-Control", "no-cache, no-store, must-revalidate")
                     .header("Pragma", "no-cache")
-                    .header("Expires", "0")
-                    .body(BoxBody::new(Full::new(Bytes::from(contents)).map_err(|_: std::convert::Infallible| unreachable!())))
+                    .header("Expires", "0");
+
+                let mut body = Bytes::from(contents);
+
+                // Byte ranges are served uncompressed
+                if range.is_some() {
+                    return Some(crate::bridge::core::router_utils::range_response(builder, body, range));
+                }
+
+                if is_compressible(content_type) {
+                    builder = builder.header("Vary", "Accept-Encoding");
+                    if accept_gzip {
+                        body = Bytes::from(gzip_bytes(&body));
+                        builder = builder.header("Content-Encoding", "gzip");
+                    }
+                }
+
+                return Some(builder
+                    .body(BoxBody::new(Full::new(body).map_err(|_: std::convert::Infallible| unreachable!())))
                     .unwrap());
             }
         }
@@ -662,46 +965,91 @@ fn serve_static_file(path: &str) -> Option<Response<BoxBody<Bytes, Infallible>>>
             "public, max-age=31536000"
         };
 
-        return Some(Response::builder()
+        let mut builder = Response::builder()
             .status(StatusCode::OK)
             .header("Content-Type", content_type)
-            .header("Access-Control-Allow-Origin", "*")
-            .header("Cache-Control", cache_control)
-            .body(BoxBody::new(Full::new(Bytes::from(contents.to_vec())).map_err(|_: std::convert::Infallible| unreachable!())))
+            .header("Cache-Control", cache_control);
+
+        // Byte ranges are served uncompressed
+        if range.is_some() {
+            return Some(crate::bridge::core::router_utils::range_response(builder, Bytes::from_static(contents), range));
+        }
+
+        // Embedded files never change, so each is compressed once and cached
+        let body = if is_compressible(content_type) {
+            builder = builder.header("Vary", "Accept-Encoding");
+            if accept_gzip {
+                builder = builder.header("Content-Encoding", "gzip");
+                let key = file.path().to_string_lossy().to_string();
+                GZIP_CACHE.lock().unwrap()
+                    .entry(key)
+                    .or_insert_with(|| Bytes::from(gzip_bytes(contents)))
+                    .clone()
+            } else {
+                Bytes::from_static(contents)
+            }
+        } else {
+            Bytes::from_static(contents)
+        };
+
+        return Some(builder
+            .body(BoxBody::new(Full::new(body).map_err(|_: std::convert::Infallible| unreachable!())))
             .unwrap());
     }
 
     None
 }
 
-/// Handle rescan plugins request - reloads plugins from config
-fn handle_rescan_plugins() -> Response<BoxBody<Bytes, Infallible>> {
-    let plugins_dir = get_plugins_dir();
-    let mut dynamic_loader = DynamicPluginLoader::new(plugins_dir);
-
-    log::info!("🔄 Reloading plugins from config: {:?}", dynamic_loader.config_path());
+/// Gzipped embedded dist files, keyed by path
+static GZIP_CACHE: Lazy<Mutex<std::collections::HashMap<String, Bytes>>> = Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Whether the client's Accept-Encoding allows gzip (and doesn't set q=0)
+fn accepts_gzip(accept_encoding: &str) -> bool {
+    accept_encoding.split(',').any(|part| {
+        let mut params = part.split(';').map(|p| p.trim());
+        let coding = params.next().unwrap_or("");
+        let refused = params.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .map(|q| q == 0.0)
+                .unwrap_or(false)
+        });
+        (coding.eq_ignore_ascii_case("gzip") || coding == "*") && !refused
+    })
+}
 
-    match dynamic_loader.load_all_plugins() {
-        Ok(dynamic_plugins) => {
-            let count = dynamic_plugins.len();
+/// Text-like types worth compressing; images and fonts are already compressed
+fn is_compressible(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || content_type.starts_with("application/javascript")
+        || content_type.starts_with("application/json")
+        || content_type.starts_with("application/wasm")
+        || content_type.starts_with("image/svg+xml")
+}
 
-            // Update global state
-            {
-                let mut loaded = LOADED_PLUGINS.lock().unwrap();
-                *loaded = dynamic_plugins;
-            }
+/// Gzip a buffer in memory
+fn gzip_bytes(data: &[u8]) -> Vec<u8> {
+    use std::io::Write;
 
-            log::info!("🔄 Reloaded {} plugins from config", count);
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    // Writing to a Vec can't fail
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
 
+/// Handle rescan plugins request - reloads plugins from config
+/// Rebuilt plugin DLLs are reloaded and every plugin's routes are re-registered
+async fn handle_rescan_plugins() -> Response<BoxBody<Bytes, Infallible>> {
+    match rescan_plugins().await {
+        Ok(dynamic_plugins) => {
             let json = serde_json::json!({
                 "success": true,
-                "count": count
+                "count": dynamic_plugins.len()
             }).to_string();
 
             Response::builder()
                 .status(StatusCode::OK)
                 .header("Content-Type", "application/json")
-                .header("Access-Control-Allow-Origin", "*")
                 .body(full_body(&json))
                 .unwrap()
         }
@@ -712,6 +1060,201 @@ fn handle_rescan_plugins() -> Response<BoxBody<Bytes, Infallible>> {
     }
 }
 
+/// Handle POST /api/plugins/install - install a plugin zip and load it
+async fn handle_install_plugin(req: Request<Incoming>, query: &str) -> Response<BoxBody<Bytes, Infallible>> {
+    let file_name = crate::bridge::core::router_utils::parse_query_param(query, "file")
+        .unwrap_or_else(|| "plugin.zip".to_string());
+    let zip_data = match read_body_limited(req, *MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    let installed = tokio::task::spawn_blocking(move || {
+        crate::plugin_installer::PluginInstaller::new(get_plugins_dir()).install_and_load(&zip_data, &file_name)
+    }).await;
+
+    match installed {
+        Ok(Ok(result)) => {
+            let status = if result.success { StatusCode::OK } else { StatusCode::UNPROCESSABLE_ENTITY };
+            Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .body(full_body(&serde_json::to_string(&result).unwrap_or_default()))
+                .unwrap()
+        }
+        Ok(Err(e)) => error_response(StatusCode::BAD_REQUEST, &format!("Failed to install plugin: {}", e)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("Failed to install plugin: {}", e)),
+    }
+}
+
+/// Handle POST /api/plugins/{id}/reload - restart one plugin, leaving the rest alone
+async fn handle_reload_plugin(plugin_id: &str) -> Response<BoxBody<Bytes, Infallible>> {
+    match reload_plugin(plugin_id).await {
+        Ok(plugin_info) => {
+            let json = serde_json::json!({
+                "success": true,
+                "plugin_id": plugin_info.id,
+                "routes": plugin_info.routes.len()
+            }).to_string();
+
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/json")
+                .body(full_body(&json))
+                .unwrap()
+        }
+        Err(e) => {
+            log::error!("Failed to reload plugin {}: {}", plugin_id, e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("Failed to reload {}: {}", plugin_id, e))
+        }
+    }
+}
+
+/// Handle POST /api/plugins/{id}/enable|disable
+///
+/// Persists the change to webarcade.config.json, then starts or stops the
+/// plugin in the running bridge. Disabled plugins' routes answer 404.
+/// Only `plugins.<id>.enabled` is rewritten, so the rest of the file (key
+/// order, fields this build doesn't know about) is left as it was.
+async fn handle_set_plugin_enabled(plugin_id: &str, enabled: bool) -> Response<BoxBody<Bytes, Infallible>> {
+    let config_path = DynamicPluginLoader::new(get_plugins_dir()).config_path().to_path_buf();
+    let mut config = match crate::bridge::core::dynamic_plugin_loader::WebArcadeConfig::load(&config_path) {
+        Ok(config) => config,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+
+    if !config.plugins.contains_key(plugin_id) {
+        return error_response(StatusCode::NOT_FOUND, &format!("Unknown plugin: {}", plugin_id));
+    }
+    if let Err(e) = config.set_plugin_enabled(plugin_id, enabled) {
+        return error_response(StatusCode::CONFLICT, &e.to_string());
+    }
+    let saved = crate::bridge::core::dynamic_plugin_loader::edit_config_file(&config_path, |raw| {
+        let entry = crate::bridge::core::dynamic_plugin_loader::config_plugins_mut(raw)?
+            .get_mut(plugin_id)
+            .and_then(|entry| entry.as_object_mut())
+            .ok_or_else(|| anyhow::anyhow!("Plugin '{}' is not in the config", plugin_id))?;
+        entry.insert("enabled".to_string(), serde_json::Value::Bool(enabled));
+        Ok(())
+    });
+    if let Err(e) = saved {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("Failed to save config: {}", e));
+    }
+
+    let result = if enabled {
+        reload_plugin(plugin_id).await.map(|_| ())
+    } else {
+        unload_plugin(plugin_id).await;
+        let loader = DynamicPluginLoader::new(get_plugins_dir());
+        LOADED_PLUGINS.lock().unwrap().push(loader.disabled_entry(plugin_id, &config.plugins[plugin_id]));
+        Ok(())
+    };
+
+    match result {
+        Ok(()) => {
+            log::info!("🔌 Plugin {} {}", plugin_id, if enabled { "enabled" } else { "disabled" });
+            let json = serde_json::json!({
+                "success": true,
+                "plugin_id": plugin_id,
+                "enabled": enabled
+            }).to_string();
+
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/json")
+                .body(full_body(&json))
+                .unwrap()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("Plugin enabled but failed to load: {}", e)),
+    }
+}
+
+/// Stop a plugin and remove its routes and library from the running bridge
+async fn unload_plugin(plugin_id: &str) {
+    if let Some(lib) = crate::bridge::core::plugin_exports::get_plugin_library(plugin_id) {
+        stop_plugin_library(plugin_id, lib).await;
+    }
+    if let Some(router_registry) = crate::bridge::core::plugin_exports::get_global_router_registry() {
+        router_registry.unregister(plugin_id).await;
+    }
+    crate::bridge::core::plugin_exports::unload_plugin_library(plugin_id);
+    LOADED_PLUGINS.lock().unwrap().retain(|p| p.id != plugin_id);
+}
+
+/// Stop a loaded plugin, load its DLL afresh and re-register its routes
+///
+/// The old library's optional `plugin_stop` export is called first so its
+/// background work ends; the library itself is freed once in-flight requests
+/// holding it finish.
+pub async fn reload_plugin(plugin_id: &str) -> Result<PluginInfo> {
+    unload_plugin(plugin_id).await;
+
+    let running: Vec<String> = {
+        let loaded = LOADED_PLUGINS.lock().unwrap();
+        crate::bridge::core::dynamic_plugin_loader::loaded_plugin_ids(&loaded).into_iter().map(String::from).collect()
+    };
+    let running: Vec<&str> = running.iter().map(String::as_str).collect();
+
+    let mut dynamic_loader = DynamicPluginLoader::new(get_plugins_dir());
+    let plugin_info = match dynamic_loader.load_plugin(plugin_id, &running) {
+        Ok(plugin_info) => plugin_info,
+        Err(e) => {
+            // Keep it visible in /api/plugins/list as failed
+            LOADED_PLUGINS.lock().unwrap().push(PluginInfo::failed(plugin_id, e.to_string()));
+            return Err(e);
+        }
+    };
+
+    if let Some(router_registry) = crate::bridge::core::plugin_exports::get_global_router_registry() {
+        register_plugin_routes(&router_registry, &plugin_info).await;
+    }
+
+    LOADED_PLUGINS.lock().unwrap().push(plugin_info.clone());
+
+    log::info!("🔄 Reloaded plugin {}", plugin_id);
+    Ok(plugin_info)
+}
+
+/// Reload plugins from config and re-register their routes
+/// Used by /api/plugins/rescan
+pub async fn rescan_plugins() -> Result<Vec<PluginInfo>> {
+    rescan_plugins_with(DynamicPluginLoader::new(get_plugins_dir())).await
+}
+
+/// `rescan_plugins` for a specific plugins directory and config
+/// Used by the installer after a plugin is extracted
+pub async fn rescan_plugins_with(mut dynamic_loader: DynamicPluginLoader) -> Result<Vec<PluginInfo>> {
+    log::info!("🔄 Reloading plugins from config: {:?}", dynamic_loader.config_path());
+
+    let dynamic_plugins = dynamic_loader.load_all_plugins()?;
+    let count = dynamic_plugins.iter().filter(|p| p.is_loaded()).count();
+
+    // Re-register routes so reloaded DLLs are picked up, and drop routers
+    // (and libraries) of plugins that are no longer in the config
+    if let Some(router_registry) = crate::bridge::core::plugin_exports::get_global_router_registry() {
+        let previous: Vec<String> = LOADED_PLUGINS.lock().unwrap().iter().map(|p| p.id.clone()).collect();
+        for plugin_id in previous {
+            if !dynamic_plugins.iter().any(|p| p.id == plugin_id && p.is_loaded()) {
+                router_registry.unregister(&plugin_id).await;
+                crate::bridge::core::plugin_exports::unload_plugin_library(&plugin_id);
+            }
+        }
+
+        for plugin_info in &dynamic_plugins {
+            register_plugin_routes(&router_registry, plugin_info).await;
+        }
+    }
+
+    // Update global state
+    {
+        let mut loaded = LOADED_PLUGINS.lock().unwrap();
+        *loaded = dynamic_plugins.clone();
+    }
+
+    log::info!("🔄 Reloaded {} plugins from config", count);
+    Ok(dynamic_plugins)
+}
+
 /// Handle /api/config - serve the webarcade config
 fn handle_get_config() -> Response<BoxBody<Bytes, Infallible>> {
     let plugins_dir = get_plugins_dir();
@@ -726,7 +1269,6 @@ fn handle_get_config() -> Response<BoxBody<Bytes, Infallible>> {
             Response::builder()
                 .status(StatusCode::OK)
                 .header("Content-Type", "application/json")
-                .header("Access-Control-Allow-Origin", "*")
                 .body(full_body(&content))
                 .unwrap()
         }
@@ -779,7 +1321,6 @@ async fn handle_set_assets_root(req: Request<Incoming>) -> Response<BoxBody<Byte
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
@@ -801,7 +1342,6 @@ fn handle_get_assets_root() -> Response<BoxBody<Bytes, Infallible>> {
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
@@ -813,7 +1353,6 @@ fn serve_project_asset(asset_path: &str) -> Response<BoxBody<Bytes, Infallible>>
             Response::builder()
                 .status(StatusCode::OK)
                 .header("Content-Type", mime_type)
-                .header("Access-Control-Allow-Origin", "*")
                 .body(BoxBody::new(Full::new(Bytes::from(content)).map_err(|_: std::convert::Infallible| unreachable!())))
                 .unwrap()
         }
@@ -833,7 +1372,6 @@ async fn handle_static_request(req: Request<Incoming>) -> Response<BoxBody<Bytes
     if method == hyper::Method::OPTIONS {
         return Response::builder()
             .status(StatusCode::OK)
-            .header("Access-Control-Allow-Origin", "*")
             .header("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS, PATCH")
             .header("Access-Control-Allow-Headers", "Content-Type, Authorization, X-Requested-With")
             .header("Access-Control-Max-Age", "86400")
@@ -842,7 +1380,15 @@ async fn handle_static_request(req: Request<Incoming>) -> Response<BoxBody<Bytes
     }
 
     // Serve static files (or SPA fallback for paths without extension)
-    if let Some(response) = serve_static_file(&path) {
+    let accept_gzip = req.headers()
+        .get(hyper::header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(accepts_gzip)
+        .unwrap_or(false);
+    let range = req.headers()
+        .get(hyper::header::RANGE)
+        .and_then(|v| v.to_str().ok());
+    if let Some(response) = serve_static_file(&path, accept_gzip, range) {
         return response;
     }
 
@@ -860,7 +1406,6 @@ async fn handle_api_request(req: Request<Incoming>, router_registry: RouterRegis
     if method == hyper::Method::OPTIONS {
         return Response::builder()
             .status(StatusCode::OK)
-            .header("Access-Control-Allow-Origin", "*")
             .header("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS, PATCH")
             .header("Access-Control-Allow-Headers", "Content-Type, Authorization, X-Requested-With")
             .header("Access-Control-Max-Age", "86400")
@@ -870,7 +1415,16 @@ async fn handle_api_request(req: Request<Incoming>, router_registry: RouterRegis
 
     // Health check endpoint
     if path == "/health" || path == "/api/health" {
-        return health_response();
+        return health_response().await;
+    }
+
+    // Prometheus metrics endpoint
+    if path == "/metrics" && method == hyper::Method::GET {
+        return Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(full_body(&crate::bridge::core::metrics::render()))
+            .unwrap();
     }
 
     // Config endpoint
@@ -883,9 +1437,33 @@ async fn handle_api_request(req: Request<Incoming>, router_registry: RouterRegis
         return modules::system_api::handle_list_plugins();
     }
 
+    // Route table across all plugins
+    if path == "/api/routes" && method == hyper::Method::GET {
+        return modules::system_api::handle_list_routes(&router_registry).await;
+    }
+
+    // Registered services across all plugins
+    if path == "/api/services" && method == hyper::Method::GET {
+        let service_registry = crate::bridge::core::plugin_exports::get_global_service_registry();
+        return modules::system_api::handle_list_services(service_registry.as_deref()).await;
+    }
+
+    // Several service calls in one round-trip
+    if path == "/api/batch" && method == hyper::Method::POST {
+        if let Some(response) = crate::bridge::core::router_utils::check_admin_request(&req, PLUGIN_API_TOKEN.as_deref()) {
+            return response;
+        }
+        let body = match read_body_limited(req, *MAX_BODY_BYTES).await {
+            Ok(body) => body,
+            Err(response) => return response,
+        };
+        let service_registry = crate::bridge::core::plugin_exports::get_global_service_registry();
+        return modules::system_api::handle_batch(service_registry.as_deref(), &body).await;
+    }
+
     // Rescan plugins endpoint for hot reload
     if path == "/api/plugins/rescan" {
-        return handle_rescan_plugins();
+        return handle_rescan_plugins().await;
     }
 
     // Set assets root endpoint
@@ -904,12 +1482,45 @@ async fn handle_api_request(req: Request<Incoming>, router_registry: RouterRegis
         return serve_project_asset(asset_path);
     }
 
+    // Install a plugin zip (the request body): POST /api/plugins/install?file=<name>.zip
+    if path == "/api/plugins/install" && method == hyper::Method::POST {
+        if let Some(response) = crate::bridge::core::router_utils::check_admin_request(&req, PLUGIN_API_TOKEN.as_deref()) {
+            return response;
+        }
+        return handle_install_plugin(req, &query).await;
+    }
+
+    // Reload a single plugin: POST /api/plugins/{id}/reload
+    if method == hyper::Method::POST && path.starts_with("/api/plugins/") && path.ends_with("/reload") {
+        let plugin_id = path["/api/plugins/".len()..path.len() - "/reload".len()].trim_matches('/');
+        if !plugin_id.is_empty() && !plugin_id.contains('/') {
+            if let Some(response) = crate::bridge::core::router_utils::check_admin_request(&req, PLUGIN_API_TOKEN.as_deref()) {
+                return response;
+            }
+            return handle_reload_plugin(plugin_id).await;
+        }
+    }
+
+    // Toggle a plugin at runtime: POST /api/plugins/{id}/enable or /disable
+    if method == hyper::Method::POST && path.starts_with("/api/plugins/") {
+        let rest = &path["/api/plugins/".len()..];
+        if let Some((plugin_id, action @ ("enable" | "disable"))) = rest.split_once('/') {
+            if let Some(response) = crate::bridge::core::router_utils::check_admin_request(&req, PLUGIN_API_TOKEN.as_deref()) {
+                return response;
+            }
+            return handle_set_plugin_enabled(plugin_id, action == "enable").await;
+        }
+    }
+
     if path.starts_with("/api/plugins/") && path.len() > 13 {
         let parts: Vec<&str> = path[13..].split('/').collect();
         if parts.len() >= 2 {
             let plugin_id = parts[0];
             let file_path = parts[1..].join("/");
-            return modules::system_api::handle_serve_plugin_file(plugin_id, &file_path);
+            let if_none_match = req.headers()
+                .get(hyper::header::IF_NONE_MATCH)
+                .and_then(|v| v.to_str().ok());
+            return modules::system_api::handle_serve_plugin_file(plugin_id, &file_path, if_none_match);
         }
     }
 
@@ -946,12 +1557,84 @@ async fn handle_api_request(req: Request<Incoming>, router_registry: RouterRegis
     error_response(StatusCode::NOT_FOUND, &format!("API route not found: {}", path))
 }
 
-fn health_response() -> Response<BoxBody<Bytes, Infallible>> {
-    let json = serde_json::json!({"status": "ok", "message": "WebArcade Bridge is ready"}).to_string();
+/// Warning text when a payload exceeds `threshold` bytes, `None` otherwise
+fn large_payload_warning(what: &str, bytes: u64, threshold: u64) -> Option<String> {
+    if bytes <= threshold {
+        return None;
+    }
+    Some(format!(
+        "{} is {:.1} MB (warning threshold {:.1} MB)",
+        what,
+        bytes as f64 / (1024.0 * 1024.0),
+        threshold as f64 / (1024.0 * 1024.0)
+    ))
+}
+
+/// Plugin label for request metrics
+/// Bridge endpoints share "bridge"; unknown first segments are lumped into
+/// "unmatched" so 404 probes can't create unbounded label values.
+async fn metrics_plugin_label(path: &str, router_registry: &RouterRegistry) -> String {
+    if path.starts_with("/api/") || path.starts_with("/assets/") || path == "/health" || path == "/metrics" {
+        return "bridge".to_string();
+    }
+
+    let plugin_name = path.trim_start_matches('/').split('/').next().unwrap_or("");
+    if router_registry.has_plugin(plugin_name).await {
+        plugin_name.to_string()
+    } else {
+        "unmatched".to_string()
+    }
+}
+
+/// One structured access-log line (body_bytes is null for streamed bodies)
+fn access_log_line(
+    method: &str,
+    path: &str,
+    plugin: &str,
+    status: u16,
+    duration: std::time::Duration,
+    body_bytes: Option<u64>,
+) -> String {
+    serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        "method": method,
+        "path": path,
+        "plugin": plugin,
+        "status": status,
+        "duration_ms": duration.as_micros() as f64 / 1000.0,
+        "body_bytes": body_bytes,
+    }).to_string()
+}
+
+/// Probe the bridge's subsystems for /health
+/// Answers 503 if a critical check (the database) fails; other checks are informational
+async fn health_response() -> Response<BoxBody<Bytes, Infallible>> {
+    let db_path = crate::bridge::core::database::default_database_path();
+    let database = tokio::task::spawn_blocking(move || crate::bridge::core::database::ping(&db_path))
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .and_then(|result| result);
+
+    let database_check = match &database {
+        Ok(()) => serde_json::json!({ "status": "ok" }),
+        Err(e) => serde_json::json!({ "status": "error", "error": e.to_string() }),
+    };
+    let healthy = database.is_ok();
+
+    let json = serde_json::json!({
+        "status": if healthy { "ok" } else { "error" },
+        "message": if healthy { "WebArcade Bridge is ready" } else { "WebArcade Bridge is unhealthy" },
+        "uptime_secs": SERVER_STARTED.elapsed().as_secs(),
+        "checks": {
+            "database": database_check,
+            "plugins": { "status": "ok", "loaded": LOADED_PLUGINS.lock().unwrap().iter().filter(|p| p.is_loaded()).count() },
+            "websocket": { "status": "ok", "connections": WebSocketBridge::connection_count() }
+        }
+    }).to_string();
+
     Response::builder()
-        .status(StatusCode::OK)
+        .status(if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE })
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
@@ -961,11 +1644,333 @@ fn error_response(status: StatusCode, message: &str) -> Response<BoxBody<Bytes,
     Response::builder()
         .status(status)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
 
+/// Where a streamed upload for one request is written ({data_local_dir}/{app}/uploads)
+///
+/// The name is random, so concurrent uploads never share a file and other
+/// local users can't predict (or pre-create) it.
+fn upload_temp_path() -> PathBuf {
+    let name: u128 = rand::random();
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(env!("CARGO_PKG_NAME"))
+        .join("uploads")
+        .join(format!("{:032x}.upload", name))
+}
+
+/// Content type of a request body, sniffed from its leading bytes
+///
+/// None for empty and multipart bodies (the parts are sniffed separately).
+/// Streamed bodies are sniffed from the start of their temp file.
+fn upload_content_type(body: &[u8], body_path: Option<&std::path::Path>, declared: Option<&str>) -> Option<String> {
+    let declared = declared.unwrap_or("");
+    if declared.contains("multipart/") {
+        return None;
+    }
+
+    let mut head = Vec::new();
+    let head = match body_path {
+        Some(path) => {
+            use std::io::Read;
+            let file = std::fs::File::open(path).ok()?;
+            file.take(8192).read_to_end(&mut head).ok()?;
+            &head[..]
+        }
+        None => &body[..body.len().min(8192)],
+    };
+    if head.is_empty() {
+        return None;
+    }
+    Some(crate::bridge::core::router_utils::sniff_content_type(head, declared))
+}
+
+/// Temp upload file, removed on drop unless the handler already moved it
+struct TempUpload(Option<PathBuf>);
+
+impl Drop for TempUpload {
+    fn drop(&mut self) {
+        if let Some(path) = &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Write a request body to `path` frame by frame, refusing anything over `limit` bytes
+///
+/// Same limits and error responses as `read_body_limited`, but the body is
+/// never held in memory. `path` must not exist yet; the partial file is
+/// removed on error.
+async fn stream_body_to_file<B>(req: Request<B>, limit: usize, path: &std::path::Path) -> std::result::Result<u64, Response<BoxBody<Bytes, Infallible>>>
+where
+    B: hyper::body::Body + Unpin,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let too_large = || error_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        &format!("Request body exceeds the {} byte limit", limit),
+    );
+    let write_failed = |e: std::io::Error| error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        &format!("Failed to store request body: {}", e),
+    );
+
+    let declared_length = req.headers()
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_length.map(|len| len > limit as u64).unwrap_or(false) {
+        return Err(too_large());
+    }
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(write_failed)?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await
+        .map_err(write_failed)?;
+    let mut body = req.into_body();
+
+    let result = async {
+        let mut written: u64 = 0;
+        while let Some(frame) = body.frame().await {
+            let frame = frame.map_err(|e| {
+                let e: Box<dyn std::error::Error + Send + Sync> = e.into();
+                error_response(StatusCode::BAD_REQUEST, &format!("Failed to read request body: {}", e))
+            })?;
+            if let Ok(data) = frame.into_data() {
+                written += data.len() as u64;
+                if written > limit as u64 {
+                    return Err(too_large());
+                }
+                file.write_all(&data).await.map_err(write_failed)?;
+            }
+        }
+        file.flush().await.map_err(write_failed)?;
+        Ok(written)
+    }.await;
+
+    drop(file);
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+    result
+}
+
+/// Collect a request body, refusing anything over `limit` bytes
+///
+/// Checks Content-Length up front and also caps the bytes actually read, so
+/// a chunked or lying client can't buffer more than `limit`. Returns a 413
+/// (or 400 if the body can't be read) to send back as-is.
+async fn read_body_limited<B>(req: Request<B>, limit: usize) -> std::result::Result<Bytes, Response<BoxBody<Bytes, Infallible>>>
+where
+    B: hyper::body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let too_large = || error_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        &format!("Request body exceeds the {} byte limit", limit),
+    );
+
+    let declared_length = req.headers()
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_length.map(|len| len > limit as u64).unwrap_or(false) {
+        return Err(too_large());
+    }
+
+    match http_body_util::Limited::new(req.into_body(), limit).collect().await {
+        Ok(collected) => Ok(collected.to_bytes()),
+        Err(e) if e.downcast_ref::<http_body_util::LengthLimitError>().is_some() => Err(too_large()),
+        Err(e) => Err(error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Failed to read request body: {}", e),
+        )),
+    }
+}
+
+/// Call a plugin's FFI handler and copy out its response string.
+///
+/// Handler signature: extern "C" fn(*const u8, usize, *const ()) -> *const u8
+/// Args: request_json_ptr, request_json_len, runtime_ptr -> response_json_ptr
+fn call_plugin_handler(lib: &libloading::Library, handler_name: &str, request_json: &str) -> std::result::Result<String, String> {
+    // Handlers run their futures on the shared plugin runtime instead of
+    // building one per call; older DLLs ignore this and make their own
+    let runtime_ptr = crate::bridge::core::plugin_exports::get_shared_runtime_ptr();
+
+    let handler_fn: libloading::Symbol<extern "C" fn(*const u8, usize, *const ()) -> *const u8> = unsafe {
+        lib.get(handler_name.as_bytes())
+    }.map_err(|e| format!("Handler function '{}' not found: {}", handler_name, e))?;
+
+    // Call the handler with full HTTP context
+    let ptr = handler_fn(request_json.as_ptr(), request_json.len(), runtime_ptr);
+    if ptr.is_null() {
+        return Err("Handler returned null".to_string());
+    }
+
+    // Read the response JSON string from the pointer
+    let response_str = unsafe {
+        let c_str = std::ffi::CStr::from_ptr(ptr as *const i8);
+        c_str.to_string_lossy().into_owned()
+    };
+
+    // Free the string (if the plugin exports free_string)
+    let free_result: std::result::Result<libloading::Symbol<extern "C" fn(*mut u8)>, _> = unsafe {
+        lib.get(b"free_string")
+    };
+    if let Ok(free_fn) = free_result {
+        free_fn(ptr as *mut u8);
+    }
+
+    Ok(response_str)
+}
+
+/// Extract a readable message from a panic payload
+///
+/// Only host-side panics in debug builds ever get here: the release profile
+/// sets `panic = "abort"`, and a panic inside a plugin DLL can't unwind
+/// through its `extern "C"` exports, so either way the process aborts.
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Finish a plugin's whole-body FFI response
+///
+/// A 200 for a GET or HEAD that sent `Range` is cut down to the requested
+/// bytes (206/416), so plugins serving media don't have to handle seeking
+/// themselves. Range means nothing for other methods (RFC 9110), so their
+/// responses pass through whole.
+fn ffi_body_response(builder: hyper::http::response::Builder, method: &hyper::Method, status: u16, body: Bytes, range: Option<&str>) -> Response<BoxBody<Bytes, Infallible>> {
+    let rangeable = *method == hyper::Method::GET || *method == hyper::Method::HEAD;
+    if rangeable && status == 200 && range.is_some() {
+        return crate::bridge::core::router_utils::range_response(builder, body, range);
+    }
+    builder.body(BoxBody::new(Full::new(body))).unwrap()
+}
+
+/// Apply the headers from a plugin's FFI response to a response builder.
+///
+/// Accepts either an object whose values are a string or a list of strings
+/// (`{"Vary": "Origin", "Set-Cookie": ["a=1", "b=2"]}`) or a list of
+/// `[name, value]` pairs, so repeated headers survive the round trip.
+fn apply_ffi_headers(
+    mut builder: hyper::http::response::Builder,
+    headers: Option<&serde_json::Value>,
+) -> hyper::http::response::Builder {
+    let mut pairs: Vec<(&str, &str)> = Vec::new();
+
+    match headers {
+        Some(serde_json::Value::Object(map)) => {
+            for (key, value) in map {
+                match value {
+                    serde_json::Value::String(v) => pairs.push((key.as_str(), v.as_str())),
+                    serde_json::Value::Array(values) => {
+                        for v in values.iter().filter_map(|v| v.as_str()) {
+                            pairs.push((key.as_str(), v));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some(serde_json::Value::Array(list)) => {
+            for pair in list {
+                if let (Some(key), Some(value)) = (
+                    pair.get(0).and_then(|v| v.as_str()),
+                    pair.get(1).and_then(|v| v.as_str()),
+                ) {
+                    pairs.push((key, value));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    for (key, value) in pairs {
+        // Builder::header appends, so repeated names become repeated header lines
+        builder = builder.header(key, value);
+    }
+
+    builder
+}
+
+/// Build a response body that streams chunks out of a plugin.
+///
+/// The plugin returns a `stream_id` in its FFI response and exports:
+/// - `next_chunk(stream_id, *mut len) -> *const u8` - next chunk, or null at end of stream
+/// - `free_chunk(ptr, len)` (optional) - release a chunk returned by next_chunk
+/// - `close_stream(stream_id)` (optional) - called if the client disconnects early
+///
+/// Returns None if the plugin doesn't export `next_chunk`.
+fn stream_plugin_body(lib: Arc<libloading::Library>, stream_id: u64) -> Option<BoxBody<Bytes, Infallible>> {
+    use futures_util::StreamExt;
+
+    type NextChunkFn = extern "C" fn(u64, *mut usize) -> *const u8;
+    type FreeChunkFn = extern "C" fn(*mut u8, usize);
+    type CloseStreamFn = extern "C" fn(u64);
+
+    if unsafe { lib.get::<NextChunkFn>(b"next_chunk") }.is_err() {
+        return None;
+    }
+
+    // Small buffer so a slow client applies backpressure to the plugin
+    let (tx, rx) = tokio::sync::mpsc::channel::<Bytes>(4);
+
+    tokio::spawn(async move {
+        loop {
+            let chunk_lib = lib.clone();
+
+            // Pulling a chunk can block (disk reads, encoding), keep it off the async workers
+            let chunk = tokio::task::spawn_blocking(move || {
+                let next_chunk: libloading::Symbol<NextChunkFn> = unsafe { chunk_lib.get(b"next_chunk").ok()? };
+                let mut len: usize = 0;
+                let ptr = next_chunk(stream_id, &mut len);
+                if ptr.is_null() {
+                    return None;
+                }
+
+                let data = unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec();
+                if let Ok(free_chunk) = unsafe { chunk_lib.get::<FreeChunkFn>(b"free_chunk") } {
+                    free_chunk(ptr as *mut u8, len);
+                }
+                Some(Bytes::from(data))
+            }).await.ok().flatten();
+
+            let bytes = match chunk {
+                Some(bytes) => bytes,
+                None => break,
+            };
+
+            if tx.send(bytes).await.is_err() {
+                // Client went away - let the plugin release the stream early
+                if let Ok(close_stream) = unsafe { lib.get::<CloseStreamFn>(b"close_stream") } {
+                    close_stream(stream_id);
+                }
+                break;
+            }
+        }
+    });
+
+    let stream = tokio_stream::wrappers::ReceiverStream::new(rx)
+        .map(|bytes| Ok::<_, Infallible>(hyper::body::Frame::data(bytes)));
+
+    Some(BoxBody::new(http_body_util::StreamBody::new(stream)))
+}
+
 fn full_body(s: &str) -> BoxBody<Bytes, Infallible> {
     use http_body_util::BodyExt;
 
@@ -974,3 +1979,274 @@ fn full_body(s: &str) -> BoxBody<Bytes, Infallible> {
         .map_err(|_: std::convert::Infallible| unreachable!())
         .boxed()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_body_over_limit_is_rejected_with_413() {
+        let req = Request::new(Full::new(Bytes::from(vec![0u8; 2048])));
+        let response = read_body_limited(req, 1024).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let req = Request::builder()
+            .header("content-length", "999999")
+            .body(Full::new(Bytes::from_static(b"{}")))
+            .unwrap();
+        let response = read_body_limited(req, 1024).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_over_limit_post_never_reaches_plugin_handler() {
+        let plugin_id = format!("body_limit_{}", std::process::id());
+        let plugin_info = PluginInfo {
+            status: crate::bridge::core::PluginStatus::Loaded,
+            error: None,
+            routes: vec![serde_json::json!({
+                "method": "POST", "path": "/upload", "handler": "handle_upload", "max_body_bytes": 1024
+            })],
+            ..PluginInfo::failed(&plugin_id, String::new())
+        };
+        let registry = RouterRegistry::new();
+        register_plugin_routes(&registry, &plugin_info).await;
+        let url = crate::bridge::core::plugin_router::serve_for_test(registry, &plugin_id).await;
+        let client = reqwest::Client::new();
+
+        // Every dispatch towards the DLL handler records its request size first
+        let handler_calls = || {
+            let series = format!("bridge_plugin_payload_bytes_count{{plugin=\"{}\",route=\"/upload\",direction=\"request\"}} ", plugin_id);
+            crate::bridge::core::metrics::render().lines()
+                .find_map(|line| line.strip_prefix(series.as_str()).and_then(|n| n.parse::<u64>().ok()))
+                .unwrap_or(0)
+        };
+
+        let rejected = client.post(format!("{}/upload", url)).body(vec![0u8; 4096]).send().await.unwrap();
+        assert_eq!(rejected.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(handler_calls(), 0);
+
+        // Within the limit the request goes on to the handler (no DLL here, hence 500)
+        let accepted = client.post(format!("{}/upload", url)).body(vec![0u8; 512]).send().await.unwrap();
+        assert_eq!(accepted.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(accepted.text().await.unwrap().contains("Plugin library not found"));
+        assert_eq!(handler_calls(), 1);
+    }
+
+    #[test]
+    fn test_upload_content_type_ignores_declared_html() {
+        let script = b"<script>alert(1)</script>";
+        assert_eq!(upload_content_type(script, None, Some("text/html")).as_deref(), Some("application/octet-stream"));
+        assert_eq!(upload_content_type(b"a,b\n1,2", None, Some("text/csv")).as_deref(), Some("text/csv"));
+        assert_eq!(upload_content_type(b"", None, Some("text/plain")), None);
+        assert_eq!(upload_content_type(script, None, Some("multipart/form-data; boundary=x")), None);
+    }
+
+    #[test]
+    fn test_large_payload_warning_threshold() {
+        assert_eq!(large_payload_warning("Response", 1024, 1024), None);
+        assert_eq!(
+            large_payload_warning("Request context", 3 * 1024 * 1024, 1024 * 1024).as_deref(),
+            Some("Request context is 3.0 MB (warning threshold 1.0 MB)")
+        );
+    }
+
+    #[test]
+    fn test_access_log_line_is_one_json_object() {
+        let line = access_log_line("GET", "/currency/balance", "currency", 200, std::time::Duration::from_millis(12), Some(42));
+        assert!(!line.contains('\n'));
+
+        let entry: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(entry["plugin"], "currency");
+        assert_eq!(entry["status"], 200);
+        assert_eq!(entry["duration_ms"], 12.0);
+        assert_eq!(entry["body_bytes"], 42);
+        assert!(entry["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_body_is_written_to_file_and_removed_when_too_large() {
+        let dir = std::env::temp_dir().join(format!("webarcade-stream-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("upload.bin");
+
+        let req = Request::new(Full::new(Bytes::from(vec![7u8; 2048])));
+        assert_eq!(stream_body_to_file(req, 4096, &path).await.unwrap(), 2048);
+        assert_eq!(std::fs::read(&path).unwrap(), vec![7u8; 2048]);
+
+        // An existing file is never reused
+        let req = Request::new(Full::new(Bytes::from(vec![7u8; 16])));
+        let response = stream_body_to_file(req, 4096, &path).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(std::fs::read(&path).unwrap(), vec![7u8; 2048]);
+
+        let too_large_path = dir.join("too_large.bin");
+        let req = Request::new(Full::new(Bytes::from(vec![7u8; 8192])));
+        let response = stream_body_to_file(req, 4096, &too_large_path).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(!too_large_path.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_upload_temp_paths_are_unique() {
+        let first = upload_temp_path();
+        assert_ne!(first, upload_temp_path());
+        assert!(first.parent().unwrap().ends_with("uploads"));
+    }
+
+    #[test]
+    fn test_resolve_plugins_dir() {
+        let root = std::env::temp_dir().join(format!("webarcade-plugins-dir-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        // Override wins over everything
+        let custom = root.join("custom");
+        assert_eq!(resolve_plugins_dir(Some(custom.clone()), Some(&root.join("app/target/debug/app"))), custom);
+
+        // Development: any target layout under a crate with Cargo.toml
+        let app = root.join("app");
+        std::fs::create_dir_all(app.join("target/x86_64-pc-windows-msvc/release")).unwrap();
+        std::fs::write(app.join("Cargo.toml"), "").unwrap();
+        assert_eq!(resolve_plugins_dir(None, Some(&app.join("target/x86_64-pc-windows-msvc/release/app"))), app.join("plugins"));
+
+        // Production: plugins next to the executable
+        let install = root.join("install");
+        std::fs::create_dir_all(install.join("plugins")).unwrap();
+        assert_eq!(resolve_plugins_dir(None, Some(&install.join("app.exe"))), install.join("plugins"));
+
+        // macOS bundle: Contents/MacOS/app -> Contents/Resources/plugins
+        let contents = root.join("App.app/Contents");
+        std::fs::create_dir_all(contents.join("MacOS")).unwrap();
+        std::fs::create_dir_all(contents.join("Resources/plugins")).unwrap();
+        let resolved = resolve_plugins_dir(None, Some(&contents.join("MacOS/app")));
+        assert_eq!(resolved, contents.join("MacOS/../Resources/plugins"));
+
+        // Nothing found: fall back to next to the executable
+        let bare = root.join("bare");
+        std::fs::create_dir_all(&bare).unwrap();
+        assert_eq!(resolve_plugins_dir(None, Some(&bare.join("app"))), bare.join("plugins"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_body_within_limit_is_collected() {
+        let req = Request::new(Full::new(Bytes::from_static(b"{\"ok\":true}")));
+        assert_eq!(read_body_limited(req, 1024).await.unwrap(), Bytes::from_static(b"{\"ok\":true}"));
+    }
+
+    #[test]
+    fn test_accepts_gzip() {
+        assert!(accepts_gzip("gzip, deflate, br"));
+        assert!(accepts_gzip("br;q=1.0, GZIP;q=0.5"));
+        assert!(accepts_gzip("*"));
+        assert!(!accepts_gzip("gzip;q=0"));
+        assert!(!accepts_gzip("br, deflate"));
+        assert!(!accepts_gzip(""));
+    }
+
+    #[test]
+    fn test_gzip_bytes_round_trip() {
+        use std::io::Read;
+
+        let original = "console.log('hello');\n".repeat(100);
+        let compressed = gzip_bytes(original.as_bytes());
+        assert!(compressed.len() < original.len());
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&compressed[..]).read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, original);
+        assert!(is_compressible("application/javascript; charset=utf-8"));
+        assert!(!is_compressible("font/woff2"));
+    }
+
+    #[test]
+    fn test_validate_ports() {
+        assert_eq!(validate_ports("3000", "3001", "3002").unwrap(), (3000, 3001, 3002));
+        assert!(validate_ports("3000", "3000", "3002").is_err());
+        assert!(validate_ports("3000", "3001", "70000").is_err());
+        assert!(validate_ports("0", "3001", "3002").is_err());
+        assert!(validate_ports("abc", "3001", "3002").is_err());
+    }
+
+    #[test]
+    fn test_parse_bind_host() {
+        assert!(parse_bind_host("127.0.0.1").unwrap().is_loopback());
+        assert!(!parse_bind_host("0.0.0.0").unwrap().is_loopback());
+        assert!(parse_bind_host("::1").unwrap().is_loopback());
+        assert!(parse_bind_host("localhost:3001").is_err());
+    }
+
+    #[test]
+    fn test_ffi_headers_keep_repeated_set_cookie() {
+        let headers = serde_json::json!({
+            "Content-Type": "text/plain",
+            "Set-Cookie": ["session=abc; HttpOnly", "theme=dark; Path=/"]
+        });
+        let response = apply_ffi_headers(Response::builder(), Some(&headers)).body(()).unwrap();
+
+        let cookies: Vec<_> = response.headers().get_all("set-cookie").iter().collect();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0], "session=abc; HttpOnly");
+        assert_eq!(cookies[1], "theme=dark; Path=/");
+        assert_eq!(response.headers().get("content-type").unwrap(), "text/plain");
+    }
+
+    #[test]
+    fn test_panic_message_downcasts_payload() {
+        let payload = std::panic::catch_unwind(|| panic!("boom {}", 42)).unwrap_err();
+        assert_eq!(panic_message(payload), "boom 42");
+
+        let payload = std::panic::catch_unwind(|| panic!("static boom")).unwrap_err();
+        assert_eq!(panic_message(payload), "static boom");
+    }
+
+    #[tokio::test]
+    async fn test_ffi_body_response_honours_range_on_200() {
+        let body = Bytes::from_static(b"0123456789");
+
+        let response = ffi_body_response(Response::builder().status(200), &hyper::Method::GET, 200, body.clone(), Some("bytes=2-5"));
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()["content-range"], "bytes 2-5/10");
+        assert_eq!(response.into_body().collect().await.unwrap().to_bytes(), "2345");
+
+        let response = ffi_body_response(Response::builder().status(200), &hyper::Method::GET, 200, body.clone(), Some("bytes=20-"));
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+        // No Range, or a non-200 from the plugin: body passes through untouched
+        let response = ffi_body_response(Response::builder().status(200), &hyper::Method::GET, 200, body.clone(), None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.into_body().collect().await.unwrap().to_bytes(), "0123456789");
+
+        let response = ffi_body_response(Response::builder().status(404), &hyper::Method::GET, 404, body, Some("bytes=2-5"));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.into_body().collect().await.unwrap().to_bytes(), "0123456789");
+    }
+
+    #[tokio::test]
+    async fn test_ffi_body_response_ignores_range_on_post() {
+        let body = Bytes::from_static(b"0123456789");
+
+        let response = ffi_body_response(Response::builder().status(200), &hyper::Method::POST, 200, body.clone(), Some("bytes=2-5"));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.into_body().collect().await.unwrap().to_bytes(), "0123456789");
+
+        let response = ffi_body_response(Response::builder().status(200), &hyper::Method::HEAD, 200, body, Some("bytes=2-5"));
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    }
+
+    #[test]
+    fn test_ffi_headers_accept_pair_list() {
+        let headers = serde_json::json!([
+            ["Vary", "Origin"],
+            ["Vary", "Accept-Encoding"],
+            ["Access-Control-Allow-Origin", "http://localhost:3000"]
+        ]);
+        let response = apply_ffi_headers(Response::builder(), Some(&headers)).body(()).unwrap();
+
+        assert_eq!(response.headers().get_all("vary").iter().count(), 2);
+        assert_eq!(response.headers()["access-control-allow-origin"], "http://localhost:3000");
+    }
+}
@@ -4,7 +4,9 @@ use hyper::body::Bytes;
 use std::convert::Infallible;
 use std::path::PathBuf;
 
-use crate::bridge::core::dynamic_plugin_loader::DynamicPluginLoader;
+use crate::bridge::core::dynamic_plugin_loader::{DynamicPluginLoader, PluginInfo, PluginListing};
+use crate::bridge::core::plugin_router::RouterRegistry;
+use crate::bridge::core::services::ServiceRegistry;
 
 /// Check if we're running in development mode
 pub fn is_dev_mode() -> bool {
@@ -32,78 +34,19 @@ pub fn get_repo_root() -> Option<PathBuf> {
     Some(repo_root.to_path_buf())
 }
 
-/// Get the plugins directory based on environment
-/// - Development: {repo_root}/build/plugins (built plugins)
-/// - Production: {exe_dir}/plugins (next to the executable)
+/// Get the plugins directory (same resolution as the bridge, including
+/// the WEBARCADE_PLUGINS_DIR override)
 pub fn get_plugins_dir() -> PathBuf {
-    let exe_path = std::env::current_exe().ok();
-    let exe_dir = exe_path.as_ref()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()));
-
-    if is_dev_mode() {
-        // Development: use repo root's build/plugins/ directory for built plugins
-        if let Some(repo_root) = get_repo_root() {
-            let build_plugins_dir = repo_root.join("build").join("plugins");
-            log::info!("🔍 Dev mode plugins dir: {:?} (exists: {})", build_plugins_dir, build_plugins_dir.exists());
-            if build_plugins_dir.exists() || std::fs::create_dir_all(&build_plugins_dir).is_ok() {
-                return build_plugins_dir;
-            }
-        }
-        // Fallback: try current directory
-        let fallback = std::env::current_dir()
-            .unwrap_or_default()
-            .join("build")
-            .join("plugins");
-        log::info!("🔍 Dev mode fallback plugins dir: {:?}", fallback);
-        fallback
-    } else {
-        // Production: try multiple locations
-        // 1. First check next to executable (Windows MSI installs here)
-        if let Some(ref dir) = exe_dir {
-            let plugins_dir = dir.join("plugins");
-            if plugins_dir.exists() {
-                return plugins_dir;
-            }
-        }
-
-        // 2. Check in Resources folder (macOS .app bundle)
-        if let Some(ref dir) = exe_dir {
-            let resources_plugins = dir.join("../Resources/plugins");
-            if resources_plugins.exists() {
-                return resources_plugins;
-            }
-        }
-
-        // 3. Fallback to exe directory even if plugins folder doesn't exist yet
-        exe_dir
-            .unwrap_or_default()
-            .join("plugins")
-    }
+    crate::bridge::get_plugins_dir()
 }
 
 /// Handle /api/plugins/list - list runtime plugins
-/// Now reads plugin info from the global loaded plugins state
+/// Includes failed and disabled plugins with their status, error and artifact path
 pub fn handle_list_plugins() -> Response<BoxBody<Bytes, Infallible>> {
     // Get the loaded plugins from the global state
     let loaded_plugins = crate::bridge::LOADED_PLUGINS.lock().unwrap();
 
-    let mut plugins = Vec::new();
-
-    for plugin_info in loaded_plugins.iter() {
-        let plugin_metadata = serde_json::json!({
-            "id": plugin_info.id,
-            "name": plugin_info.name,
-            "version": plugin_info.version,
-            "description": plugin_info.description,
-            "author": plugin_info.author,
-            "priority": plugin_info.priority,
-            "routes": plugin_info.routes,
-            "has_plugin_js": plugin_info.has_frontend,
-            "has_dll": plugin_info.has_backend
-        });
-
-        plugins.push(plugin_metadata);
-    }
+    let plugins: Vec<PluginListing> = loaded_plugins.iter().map(PluginInfo::listing).collect();
 
     let json = serde_json::json!({
         "plugins": plugins
@@ -112,14 +55,104 @@ pub fn handle_list_plugins() -> Response<BoxBody<Bytes, Infallible>> {
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
+        .body(full_body(&json))
+        .unwrap()
+}
+
+/// Handle /api/routes - list every registered route, grouped by plugin
+/// Plugins and routes are sorted so the output is stable and diffable
+pub async fn handle_list_routes(router_registry: &RouterRegistry) -> Response<BoxBody<Bytes, Infallible>> {
+    let routes = router_registry.list_routes().await;
+
+    let json = serde_json::json!({
+        "routes": routes
+    }).to_string();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(full_body(&json))
+        .unwrap()
+}
+
+/// Handle /api/services - list every registered service as plugin/name pairs
+pub async fn handle_list_services(service_registry: Option<&ServiceRegistry>) -> Response<BoxBody<Bytes, Infallible>> {
+    let services: Vec<serde_json::Value> = match service_registry {
+        Some(registry) => registry.list().await
+            .into_iter()
+            .map(|(plugin_id, name)| serde_json::json!({ "plugin_id": plugin_id, "name": name }))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let json = serde_json::json!({
+        "services": services
+    }).to_string();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(full_body(&json))
+        .unwrap()
+}
+
+/// Most calls accepted in one POST /api/batch
+const MAX_BATCH_CALLS: usize = 50;
+
+/// Handle POST /api/batch - run several service calls in one request
+///
+/// Body: `[{"plugin": "currency", "service": "get_balance", "input": {...}}, ...]`
+/// Answers 200 with one entry per call, in order: `{"ok": true, "result": ...}`
+/// or `{"ok": false, "error": {"code", "message", "details"}}`.
+pub async fn handle_batch(service_registry: Option<&ServiceRegistry>, body: &[u8]) -> Response<BoxBody<Bytes, Infallible>> {
+    let Some(registry) = service_registry else {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "Service registry not initialized");
+    };
+
+    let calls: Vec<serde_json::Value> = match serde_json::from_slice(body) {
+        Ok(serde_json::Value::Array(calls)) => calls,
+        Ok(_) => return error_response(StatusCode::BAD_REQUEST, "Batch body must be a JSON array of calls"),
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &format!("Invalid JSON: {}", e)),
+    };
+    if calls.len() > MAX_BATCH_CALLS {
+        return error_response(StatusCode::BAD_REQUEST, &format!("Batch has {} calls; the limit is {}", calls.len(), MAX_BATCH_CALLS));
+    }
+
+    let mut service_calls = Vec::with_capacity(calls.len());
+    for (index, call) in calls.into_iter().enumerate() {
+        let plugin = call.get("plugin").and_then(|v| v.as_str());
+        let service = call.get("service").and_then(|v| v.as_str());
+        match (plugin, service) {
+            (Some(plugin), Some(service)) => {
+                let input = call.get("input").cloned().unwrap_or(serde_json::Value::Null);
+                service_calls.push((format!("{}.{}", plugin, service), input));
+            }
+            _ => return error_response(StatusCode::BAD_REQUEST, &format!("Call {} needs \"plugin\" and \"service\"", index)),
+        }
+    }
+
+    let results: Vec<serde_json::Value> = registry.call_batch(service_calls).await
+        .into_iter()
+        .map(|result| match result {
+            Ok(value) => serde_json::json!({ "ok": true, "result": value }),
+            Err(e) => serde_json::json!({ "ok": false, "error": crate::bridge::core::services::ServiceError::from_error(&e) }),
+        })
+        .collect();
+
+    let json = serde_json::json!({
+        "results": results
+    }).to_string();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
         .body(full_body(&json))
         .unwrap()
 }
 
 /// Handle /api/plugins/{plugin_id}/{file} - serve plugin files
 /// For plugin.js, retrieves from file (frontend-only) or embedded DLL content
-pub fn handle_serve_plugin_file(plugin_id: &str, file_path: &str) -> Response<BoxBody<Bytes, Infallible>> {
+pub fn handle_serve_plugin_file(plugin_id: &str, file_path: &str, if_none_match: Option<&str>) -> Response<BoxBody<Bytes, Infallible>> {
     // For plugin.js (legacy) or {plugin_id}.js, check if it's a frontend-only plugin first
     let expected_js_name = format!("{}.js", plugin_id);
     if file_path == "plugin.js" || file_path == expected_js_name {
@@ -130,12 +163,7 @@ pub fn handle_serve_plugin_file(plugin_id: &str, file_path: &str) -> Response<Bo
             #[cfg(feature = "locked-plugins")]
             if let Some(ref _embedded_key) = plugin_info.embedded_js {
                 if let Some(js_content) = crate::bridge::core::plugin_exports::get_embedded_js(plugin_id) {
-                    return Response::builder()
-                        .status(StatusCode::OK)
-                        .header("Content-Type", "application/javascript")
-                        .header("Access-Control-Allow-Origin", "*")
-                        .body(BoxBody::new(Full::new(Bytes::from(js_content))))
-                        .unwrap();
+                    return plugin_js_response(js_content, if_none_match);
                 }
             }
 
@@ -144,12 +172,7 @@ pub fn handle_serve_plugin_file(plugin_id: &str, file_path: &str) -> Response<Bo
                 // Frontend-only plugin - serve from file
                 match std::fs::read_to_string(frontend_path) {
                     Ok(js_content) => {
-                        return Response::builder()
-                            .status(StatusCode::OK)
-                            .header("Content-Type", "application/javascript")
-                            .header("Access-Control-Allow-Origin", "*")
-                            .body(BoxBody::new(Full::new(Bytes::from(js_content))))
-                            .unwrap();
+                        return plugin_js_response(js_content, if_none_match);
                     }
                     Err(e) => {
                         log::warn!("Failed to read frontend file for plugin {}: {}", plugin_id, e);
@@ -163,12 +186,7 @@ pub fn handle_serve_plugin_file(plugin_id: &str, file_path: &str) -> Response<Bo
         // DLL-based plugin - serve from embedded content
         match DynamicPluginLoader::get_frontend_js(plugin_id) {
             Ok(js_content) => {
-                return Response::builder()
-                    .status(StatusCode::OK)
-                    .header("Content-Type", "application/javascript")
-                    .header("Access-Control-Allow-Origin", "*")
-                    .body(BoxBody::new(Full::new(Bytes::from(js_content))))
-                    .unwrap();
+                return plugin_js_response(js_content, if_none_match);
             }
             Err(e) => {
                 log::warn!("Failed to get frontend for plugin {}: {}", plugin_id, e);
@@ -181,6 +199,24 @@ pub fn handle_serve_plugin_file(plugin_id: &str, file_path: &str) -> Response<Bo
     error_response(StatusCode::NOT_FOUND, "File not found - plugins are now self-contained in DLLs")
 }
 
+/// Serve a plugin's frontend JS with an ETag
+/// Marked no-cache so the browser revalidates and gets a 304 when unchanged
+fn plugin_js_response(js_content: impl Into<Bytes>, if_none_match: Option<&str>) -> Response<BoxBody<Bytes, Infallible>> {
+    let js_content: Bytes = js_content.into();
+    let etag = crate::bridge::core::router_utils::content_etag(&js_content);
+    if crate::bridge::core::router_utils::etag_matches(if_none_match, &etag) {
+        return crate::bridge::core::router_utils::not_modified(&etag);
+    }
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/javascript")
+        .header("Cache-Control", "no-cache")
+        .header("ETag", etag)
+        .body(BoxBody::new(Full::new(js_content)))
+        .unwrap()
+}
+
 fn full_body(s: &str) -> BoxBody<Bytes, Infallible> {
     use http_body_util::combinators::BoxBody;
     use http_body_util::BodyExt;
@@ -192,7 +228,6 @@ fn error_response(status: StatusCode, message: &str) -> Response<BoxBody<Bytes,
     Response::builder()
         .status(status)
         .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
         .body(full_body(&json))
         .unwrap()
 }
@@ -217,6 +217,7 @@ fn main() {
                 event: WindowEvent::CloseRequested,
                 ..
             } => {
+                shutdown_bridge();
                 *control_flow = ControlFlow::Exit;
             }
             _ => {}
@@ -238,6 +239,7 @@ fn handle_ipc_command(request: &IpcRequest, window: &Window) -> IpcResponse {
         "ping" => IpcResponse::ok(id, "pong"),
 
         "close" => {
+            shutdown_bridge();
             std::process::exit(0);
         }
 
@@ -348,6 +350,14 @@ fn handle_ipc_command(request: &IpcRequest, window: &Window) -> IpcResponse {
     }
 }
 
+/// Stop the bridge and its plugins, waiting briefly so they can clean up
+fn shutdown_bridge() {
+    bridge::request_shutdown();
+    if !bridge::wait_for_shutdown(std::time::Duration::from_secs(3)) {
+        log::warn!("[BRIDGE] Shutdown timed out");
+    }
+}
+
 /// Start the bridge server in a background thread
 fn start_bridge_server() {
     log::info!("[BRIDGE] Starting integrated bridge server...");
@@ -415,5 +425,7 @@ fn load_config() -> WebArcadeConfig {
         width: 1280,
         height: 720,
         plugins: std::collections::HashMap::new(),
+        require_signed_plugins: false,
+        trusted_plugin_keys: Vec::new(),
     }
 }
@@ -1,4 +1,6 @@
 use anyhow::{anyhow, Result};
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{Read, Write};
@@ -22,15 +24,135 @@ pub struct InstallResult {
     pub plugin_name: String,
     pub plugin_id: String,
     pub message: String,
+    /// Why the zip was refused, when `success` is false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejection: Option<InstallRejection>,
+    /// IDs of the plugins the bridge has loaded after the install
+    pub loaded_plugins: Vec<String>,
+    /// The bridge couldn't pick the plugin up; the app must be restarted
+    pub requires_restart: bool,
 }
 
+/// Reasons a plugin zip is refused before anything is extracted
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", content = "detail", rename_all = "snake_case")]
+pub enum InstallRejection {
+    /// An entry escapes the plugin directory (e.g. "../../evil.dll")
+    PathTraversal(String),
+    /// An entry has an absolute path (e.g. "/etc/passwd" or "C:\\evil.dll")
+    AbsolutePath(String),
+    /// An entry is a symlink
+    Symlink(String),
+    /// Total uncompressed size is over the limit
+    TooLarge { size: u64, limit: u64 },
+    /// Number of entries is over the limit
+    TooManyFiles { count: usize, limit: usize },
+    /// No manifest.json or package.json with a "webarcade" block
+    MissingManifest,
+    /// The manifest is present but unusable
+    InvalidManifest(String),
+    /// Signed plugins are required and the zip has no signature file
+    Unsigned,
+    /// The signature doesn't match the contents or no trusted key signed it
+    InvalidSignature(String),
+}
+
+impl std::fmt::Display for InstallRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PathTraversal(name) => write!(f, "Zip entry '{}' escapes the plugin directory", name),
+            Self::AbsolutePath(name) => write!(f, "Zip entry '{}' has an absolute path", name),
+            Self::Symlink(name) => write!(f, "Zip entry '{}' is a symlink", name),
+            Self::TooLarge { size, limit } => write!(f, "Plugin is {} bytes uncompressed (limit {})", size, limit),
+            Self::TooManyFiles { count, limit } => write!(f, "Plugin has {} files (limit {})", count, limit),
+            Self::MissingManifest => write!(f, "No manifest.json or package.json with a \"webarcade\" block found in plugin zip"),
+            Self::InvalidManifest(reason) => write!(f, "Invalid plugin manifest: {}", reason),
+            Self::Unsigned => write!(f, "Plugin is not signed and signed plugins are required"),
+            Self::InvalidSignature(reason) => write!(f, "Invalid plugin signature: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for InstallRejection {}
+
+/// Name of the zip entry holding the base64 Ed25519 signature
+const SIGNATURE_FILE: &str = "signature";
+
+/// Which plugin signatures the installer accepts
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicy {
+    /// Refuse plugins without a signature
+    pub require_signed: bool,
+    /// Keys whose signatures are trusted
+    pub trusted_keys: Vec<VerifyingKey>,
+}
+
+impl SignaturePolicy {
+    /// Build the policy from `requireSignedPlugins` / `trustedPluginKeys` in webarcade.config.json
+    /// Keys that don't decode are skipped with a warning.
+    pub fn from_config(config: &crate::bridge::core::dynamic_plugin_loader::WebArcadeConfig) -> Self {
+        let trusted_keys = config.trusted_plugin_keys.iter()
+            .filter_map(|key| match decode_verifying_key(key) {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    log::warn!("Ignoring trusted plugin key {}: {}", key, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            require_signed: config.require_signed_plugins,
+            trusted_keys,
+        }
+    }
+}
+
+/// Default max total uncompressed size of a plugin zip (256 MB)
+const MAX_UNCOMPRESSED_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Default max number of entries in a plugin zip
+const MAX_FILE_COUNT: usize = 10_000;
+
 pub struct PluginInstaller {
     plugins_dir: PathBuf,
+    /// webarcade.config.json, where installed plugins are registered
+    config_path: PathBuf,
+    max_uncompressed_size: u64,
+    max_file_count: usize,
+    /// Overrides the policy read from the config file
+    signature_policy: Option<SignaturePolicy>,
 }
 
 impl PluginInstaller {
     pub fn new(plugins_dir: PathBuf) -> Self {
-        Self { plugins_dir }
+        let config_path = crate::bridge::core::dynamic_plugin_loader::default_config_path(&plugins_dir);
+        Self {
+            plugins_dir,
+            config_path,
+            max_uncompressed_size: MAX_UNCOMPRESSED_SIZE,
+            max_file_count: MAX_FILE_COUNT,
+            signature_policy: None,
+        }
+    }
+
+    /// Set which plugin signatures are accepted instead of reading them from the config
+    pub fn with_signature_policy(mut self, signature_policy: SignaturePolicy) -> Self {
+        self.signature_policy = Some(signature_policy);
+        self
+    }
+
+    /// Register installed plugins in this config file instead of the default one
+    pub fn with_config_path(mut self, config_path: PathBuf) -> Self {
+        self.config_path = config_path;
+        self
+    }
+
+    /// Override the zip-bomb limits
+    pub fn with_limits(mut self, max_uncompressed_size: u64, max_file_count: usize) -> Self {
+        self.max_uncompressed_size = max_uncompressed_size;
+        self.max_file_count = max_file_count;
+        self
     }
 
     /// Install a plugin from zip data
@@ -42,8 +164,27 @@ impl PluginInstaller {
         let mut archive = ZipArchive::new(cursor)
             .map_err(|e| anyhow!("Failed to read zip file: {}", e))?;
 
-        // Validate the plugin structure and extract manifest
-        let manifest = self.validate_plugin_structure(&mut archive)?;
+        let signature_policy = self.load_signature_policy()?;
+
+        // Refuse unsafe archives and bad manifests before touching the disk
+        let manifest = match self.check_archive(&mut archive)
+            .and_then(|_| check_signature(&signature_policy, &mut archive))
+            .and_then(|_| self.validate_plugin_structure(&mut archive))
+        {
+            Ok(manifest) => manifest,
+            Err(rejection) => {
+                log::warn!("Rejected plugin {}: {}", file_name, rejection);
+                return Ok(InstallResult {
+                    success: false,
+                    plugin_name: String::new(),
+                    plugin_id: String::new(),
+                    message: rejection.to_string(),
+                    rejection: Some(rejection),
+                    loaded_plugins: Vec::new(),
+                    requires_restart: false,
+                });
+            }
+        };
 
         log::info!(
             "Installing plugin: {} ({})",
@@ -62,6 +203,9 @@ impl PluginInstaller {
         // Extract the plugin
         self.extract_plugin(&mut archive, &plugin_install_dir)?;
 
+        // The loader only loads plugins listed in the config
+        self.register_in_config(&manifest, &plugin_install_dir)?;
+
         log::info!("Plugin {} installed successfully", manifest.id);
 
         Ok(InstallResult {
@@ -69,53 +213,198 @@ impl PluginInstaller {
             plugin_name: manifest.name.clone(),
             plugin_id: manifest.id.clone(),
             message: format!("Plugin '{}' installed successfully", manifest.name),
+            rejection: None,
+            loaded_plugins: Vec::new(),
+            requires_restart: false,
         })
     }
 
+    /// Install a plugin from zip data and have the running bridge load it
+    ///
+    /// Rescans plugins in-process so the new plugin's routes are live without
+    /// a restart. If the bridge doesn't end up with the plugin loaded (e.g. the
+    /// rescan failed), `requires_restart` is set so the frontend can prompt.
+    pub fn install_and_load(&self, zip_data: &[u8], file_name: &str) -> Result<InstallResult> {
+        let mut result = self.install_from_zip(zip_data, file_name)?;
+        if !result.success {
+            return Ok(result);
+        }
+
+        // Run on a fresh thread so this works whether or not the caller is
+        // already inside a tokio runtime
+        let loader = crate::bridge::core::dynamic_plugin_loader::DynamicPluginLoader::new(self.plugins_dir.clone())
+            .with_config_path(self.config_path.clone());
+        let rescan = std::thread::spawn(move || {
+            crate::bridge::core::plugin_exports::SHARED_RUNTIME.block_on(crate::bridge::rescan_plugins_with(loader))
+        }).join();
+
+        match rescan {
+            Ok(Ok(plugins)) => {
+                result.loaded_plugins = plugins.into_iter().filter(|p| p.is_loaded()).map(|p| p.id).collect();
+                result.requires_restart = !result.loaded_plugins.contains(&result.plugin_id);
+            }
+            Ok(Err(e)) => {
+                log::warn!("Plugin {} installed but rescan failed: {}", result.plugin_id, e);
+                result.requires_restart = true;
+            }
+            Err(_) => {
+                log::warn!("Plugin {} installed but rescan panicked", result.plugin_id);
+                result.requires_restart = true;
+            }
+        }
+
+        if result.requires_restart {
+            result.message = format!("{} - restart the app to load it", result.message);
+        }
+
+        Ok(result)
+    }
+
+    /// Add (or replace) the plugin's entry in webarcade.config.json, enabled
+    ///
+    /// `path` points into the plugin's own directory: its DLL when the
+    /// manifest says it has a backend, otherwise its JS entry point. Settings
+    /// from a previous install (config, priority, dependencies) are kept.
+    fn register_in_config(&self, manifest: &PluginManifest, install_dir: &Path) -> Result<()> {
+        use crate::bridge::core::dynamic_plugin_loader::{config_plugins_mut, dll_file_name, edit_config_file};
+
+        let has_backend = manifest.has_backend.unwrap_or(false);
+        let entry_file = if has_backend {
+            dll_file_name(&manifest.id)
+        } else {
+            [format!("{}.js", manifest.id), "plugin.js".to_string(), "index.js".to_string()]
+                .into_iter()
+                .find(|name| install_dir.join(name).is_file())
+                .unwrap_or_else(|| "plugin.js".to_string())
+        };
+
+        edit_config_file(&self.config_path, |config| {
+            let plugins = config_plugins_mut(config)?;
+            let mut entry = serde_json::json!({
+                "name": manifest.name,
+                "version": manifest.version,
+                "description": manifest.description.clone().unwrap_or_default(),
+                "author": manifest.author.clone().unwrap_or_default(),
+                "path": format!("{}/{}", manifest.id, entry_file),
+                "hasBackend": has_backend,
+                "hasFrontend": manifest.has_frontend.unwrap_or(true),
+                "priority": 100,
+                "enabled": true,
+            });
+            if let (Some(previous), Some(entry)) = (plugins.get(&manifest.id), entry.as_object_mut()) {
+                for key in ["config", "priority", "dependencies"] {
+                    if let Some(value) = previous.get(key) {
+                        entry.insert(key.to_string(), value.clone());
+                    }
+                }
+            }
+            plugins.insert(manifest.id.clone(), entry);
+            Ok(())
+        })?;
+
+        log::info!("Registered plugin {} in {:?}", manifest.id, self.config_path);
+        Ok(())
+    }
+
+    /// Reject path traversal, absolute paths, symlinks and zip bombs
+    fn check_archive(&self, archive: &mut ZipArchive<std::io::Cursor<&[u8]>>) -> std::result::Result<(), InstallRejection> {
+        if archive.len() > self.max_file_count {
+            return Err(InstallRejection::TooManyFiles { count: archive.len(), limit: self.max_file_count });
+        }
+
+        let mut total_size: u64 = 0;
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)
+                .map_err(|e| InstallRejection::InvalidManifest(format!("Failed to read zip entry: {}", e)))?;
+            let name = file.name().to_string();
+
+            if is_absolute_entry(&name) {
+                return Err(InstallRejection::AbsolutePath(name));
+            }
+            if name.split(['/', '\\']).any(|part| part == "..") || file.enclosed_name().is_none() {
+                return Err(InstallRejection::PathTraversal(name));
+            }
+            if file.unix_mode().map(|mode| mode & 0o170000 == 0o120000).unwrap_or(false) {
+                return Err(InstallRejection::Symlink(name));
+            }
+
+            total_size = total_size.saturating_add(file.size());
+            if total_size > self.max_uncompressed_size {
+                return Err(InstallRejection::TooLarge { size: total_size, limit: self.max_uncompressed_size });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The configured signature policy, unless one was set with `with_signature_policy`
+    ///
+    /// Read on every install so config edits apply without a restart. A
+    /// missing config means the defaults; an unreadable one is an error.
+    fn load_signature_policy(&self) -> Result<SignaturePolicy> {
+        if let Some(policy) = &self.signature_policy {
+            return Ok(policy.clone());
+        }
+        if !self.config_path.exists() {
+            return Ok(SignaturePolicy::default());
+        }
+        let config = crate::bridge::core::dynamic_plugin_loader::WebArcadeConfig::load(&self.config_path)?;
+        Ok(SignaturePolicy::from_config(&config))
+    }
+
     /// Validate that the zip contains a valid plugin structure
-    fn validate_plugin_structure(&self, archive: &mut ZipArchive<std::io::Cursor<&[u8]>>) -> Result<PluginManifest> {
-        // Look for manifest.json in the root or first-level directory
+    fn validate_plugin_structure(&self, archive: &mut ZipArchive<std::io::Cursor<&[u8]>>) -> std::result::Result<PluginManifest, InstallRejection> {
+        // Look for manifest.json (or package.json) in the root or first-level directory
         let mut manifest_content = None;
+        let mut package_content = None;
 
-        // First pass: find manifest.json
         for i in 0..archive.len() {
             let mut file = archive.by_index(i)
-                .map_err(|e| anyhow!("Failed to read zip entry: {}", e))?;
+                .map_err(|e| InstallRejection::InvalidManifest(format!("Failed to read zip entry: {}", e)))?;
             let file_path = file.name().to_string();
-
-            // Check if this is manifest.json
-            if file_path.ends_with("manifest.json") {
-                let mut content = String::new();
-                file.read_to_string(&mut content)
-                    .map_err(|e| anyhow!("Failed to read manifest.json: {}", e))?;
-                manifest_content = Some(content);
-                break;
+            let depth = file_path.trim_end_matches('/').matches('/').count();
+            if depth > 1 {
+                continue;
             }
-        }
 
-        let manifest_json = manifest_content
-            .ok_or_else(|| anyhow!("No manifest.json found in plugin zip"))?;
+            let target = if file_path.ends_with("manifest.json") && manifest_content.is_none() {
+                &mut manifest_content
+            } else if file_path.ends_with("package.json") && package_content.is_none() {
+                &mut package_content
+            } else {
+                continue;
+            };
 
-        let manifest: PluginManifest = serde_json::from_str(&manifest_json)
-            .map_err(|e| anyhow!("Invalid manifest.json: {}", e))?;
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .map_err(|e| InstallRejection::InvalidManifest(format!("Failed to read {}: {}", file_path, e)))?;
+            *target = Some(content);
+        }
+
+        let manifest = match (manifest_content, package_content) {
+            (Some(manifest_json), _) => serde_json::from_str::<PluginManifest>(&manifest_json)
+                .map_err(|e| InstallRejection::InvalidManifest(e.to_string()))?,
+            (None, Some(package_json)) => manifest_from_package_json(&package_json)?,
+            (None, None) => return Err(InstallRejection::MissingManifest),
+        };
 
         // Validate required fields
         if manifest.id.is_empty() {
-            return Err(anyhow!("Plugin manifest missing required field: id"));
+            return Err(InstallRejection::InvalidManifest("missing required field: id".to_string()));
         }
         if manifest.name.is_empty() {
-            return Err(anyhow!("Plugin manifest missing required field: name"));
+            return Err(InstallRejection::InvalidManifest("missing required field: name".to_string()));
         }
         if manifest.version.is_empty() {
-            return Err(anyhow!("Plugin manifest missing required field: version"));
+            return Err(InstallRejection::InvalidManifest("missing required field: version".to_string()));
         }
 
         // Validate plugin ID (must be valid directory name)
         if !manifest.id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
-            return Err(anyhow!(
+            return Err(InstallRejection::InvalidManifest(format!(
                 "Invalid plugin ID '{}': must contain only alphanumeric characters, hyphens, and underscores",
                 manifest.id
-            ));
+            )));
         }
 
         log::info!("Plugin validation passed: {}", manifest.name);
@@ -196,6 +485,11 @@ impl PluginInstaller {
                 continue;
             }
 
+            // check_archive already refused these; never write outside plugin_dir regardless
+            if is_absolute_entry(relative_path) || relative_path.split(['/', '\\']).any(|part| part == "..") {
+                return Err(anyhow!("Refusing to extract unsafe path: {}", file_path));
+            }
+
             let output_path = plugin_dir.join(relative_path);
 
             if file.is_dir() {
@@ -213,9 +507,14 @@ impl PluginInstaller {
                 let mut outfile = fs::File::create(&output_path)
                     .map_err(|e| anyhow!("Failed to create file {:?}: {}", output_path, e))?;
 
+                // Don't trust the declared size: read at most one byte past it
+                let declared_size = file.size();
                 let mut buffer = Vec::new();
-                file.read_to_end(&mut buffer)
+                file.by_ref().take(declared_size + 1).read_to_end(&mut buffer)
                     .map_err(|e| anyhow!("Failed to read file from zip: {}", e))?;
+                if buffer.len() as u64 > declared_size {
+                    return Err(anyhow!("Zip entry {} is larger than its declared size", file_path));
+                }
 
                 outfile.write_all(&buffer)
                     .map_err(|e| anyhow!("Failed to write file: {}", e))?;
@@ -229,6 +528,141 @@ impl PluginInstaller {
     }
 }
 
+/// Verify the zip's signature against the trusted keys
+///
+/// A present signature must be valid once trusted keys are configured; a
+/// missing one is only refused when the policy requires signed plugins.
+fn check_signature(policy: &SignaturePolicy, archive: &mut ZipArchive<std::io::Cursor<&[u8]>>) -> std::result::Result<(), InstallRejection> {
+    // Only read the whole plugin into memory when there is something to verify
+    if !archive.file_names().any(is_signature_entry) {
+        if policy.require_signed {
+            return Err(InstallRejection::Unsigned);
+        }
+        log::warn!("Installing unsigned plugin");
+        return Ok(());
+    }
+
+    // Nothing to verify against: treat it like an unsigned plugin
+    if policy.trusted_keys.is_empty() && !policy.require_signed {
+        log::warn!("Installing signed plugin without verifying it: no trusted plugin keys are configured");
+        return Ok(());
+    }
+
+    let (payload, signature) = signed_payload(archive)
+        .map_err(|e| InstallRejection::InvalidSignature(e.to_string()))?;
+
+    let signature = signature.ok_or(InstallRejection::Unsigned)?;
+
+    let signature_bytes = base64::engine::general_purpose::STANDARD.decode(signature.trim())
+        .map_err(|e| InstallRejection::InvalidSignature(format!("not base64: {}", e)))?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .map_err(|e| InstallRejection::InvalidSignature(e.to_string()))?;
+
+    if policy.trusted_keys.iter().any(|key| key.verify(&payload, &signature).is_ok()) {
+        log::info!("Plugin signature verified");
+        Ok(())
+    } else {
+        Err(InstallRejection::InvalidSignature("not signed by a trusted key, or contents were modified".to_string()))
+    }
+}
+
+/// Bytes covered by a plugin signature, plus the signature file's contents
+///
+/// Every file except the signature, sorted by name, as
+/// `name \0 length(u64 LE) contents`, so renaming, reordering or
+/// editing any file (DLL, manifest, assets) breaks the signature.
+fn signed_payload(archive: &mut ZipArchive<std::io::Cursor<&[u8]>>) -> Result<(Vec<u8>, Option<String>)> {
+    let mut entries = Vec::new();
+    let mut signature = None;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+
+        // Don't trust the declared size: read at most one byte past it
+        let name = file.name().to_string();
+        let declared_size = file.size();
+        let mut content = Vec::new();
+        file.by_ref().take(declared_size + 1).read_to_end(&mut content)?;
+        if content.len() as u64 > declared_size {
+            return Err(anyhow!("Zip entry {} is larger than its declared size", name));
+        }
+
+        if is_signature_entry(&name) {
+            signature = Some(String::from_utf8_lossy(&content).to_string());
+        } else {
+            entries.push((name, content));
+        }
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut payload = Vec::new();
+    for (name, content) in entries {
+        payload.extend_from_slice(name.as_bytes());
+        payload.push(0);
+        payload.extend_from_slice(&(content.len() as u64).to_le_bytes());
+        payload.extend_from_slice(&content);
+    }
+
+    Ok((payload, signature))
+}
+
+/// Whether a zip entry is the plugin's `signature` file (root or first-level directory)
+fn is_signature_entry(name: &str) -> bool {
+    name.rsplit('/').next() == Some(SIGNATURE_FILE) && name.matches('/').count() <= 1
+}
+
+/// Sign a packaged plugin zip, returning the base64 contents for its `signature` file
+pub fn sign_plugin_zip(zip_data: &[u8], signing_key: &SigningKey) -> Result<String> {
+    let mut archive = ZipArchive::new(std::io::Cursor::new(zip_data))
+        .map_err(|e| anyhow!("Failed to read zip file: {}", e))?;
+    let (payload, _) = signed_payload(&mut archive)?;
+    let signature = signing_key.sign(&payload);
+    Ok(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()))
+}
+
+/// Decode a base64 Ed25519 public key
+fn decode_verifying_key(key: &str) -> Result<VerifyingKey> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(key.trim())?;
+    let bytes: [u8; 32] = bytes.try_into()
+        .map_err(|_| anyhow!("expected 32 bytes"))?;
+    Ok(VerifyingKey::from_bytes(&bytes)?)
+}
+
+/// Whether a zip entry name is absolute on any platform ("/x", "\\x", "C:...")
+fn is_absolute_entry(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    name.starts_with('/')
+        || name.starts_with('\\')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Build a manifest from a package.json's "webarcade" block
+/// The block may set "id"; name, version, description and author come from package.json.
+fn manifest_from_package_json(content: &str) -> std::result::Result<PluginManifest, InstallRejection> {
+    let package: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| InstallRejection::InvalidManifest(format!("package.json: {}", e)))?;
+    let webarcade = package.get("webarcade")
+        .filter(|block| block.is_object())
+        .ok_or(InstallRejection::MissingManifest)?;
+
+    let text = |value: Option<&serde_json::Value>| value.and_then(|v| v.as_str()).map(|v| v.to_string());
+    let name = text(package.get("name")).unwrap_or_default();
+
+    Ok(PluginManifest {
+        id: text(webarcade.get("id")).unwrap_or_else(|| name.clone()),
+        name,
+        version: text(package.get("version")).unwrap_or_default(),
+        description: text(package.get("description")),
+        author: text(package.get("author")),
+        has_frontend: webarcade.get("has_frontend").and_then(|v| v.as_bool()),
+        has_backend: webarcade.get("has_backend").and_then(|v| v.as_bool()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -239,4 +673,187 @@ mod tests {
         let installer = PluginInstaller::new(temp_dir);
         // Just test that we can create an installer
     }
+
+    const MANIFEST: &str = r#"{"id": "demo", "name": "Demo", "version": "1.0.0"}"#;
+
+    fn build_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn install(name: &str, data: &[u8], installer: PluginInstaller) -> InstallResult {
+        installer.install_from_zip(data, name).unwrap()
+    }
+
+    fn temp_installer(name: &str) -> PluginInstaller {
+        let plugins_dir = std::env::temp_dir().join(format!("webarcade_test_installer_{}", name));
+        PluginInstaller::new(plugins_dir.clone()).with_config_path(plugins_dir.join("webarcade.config.json"))
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let data = build_zip(&[("manifest.json", MANIFEST), ("../../evil.dll", "x")]);
+        let result = install("traversal.zip", &data, temp_installer("traversal"));
+        assert!(!result.success);
+        assert_eq!(result.rejection, Some(InstallRejection::PathTraversal("../../evil.dll".to_string())));
+    }
+
+    #[test]
+    fn test_rejects_absolute_paths() {
+        let data = build_zip(&[("manifest.json", MANIFEST), ("C:/Windows/evil.dll", "x")]);
+        let result = install("absolute.zip", &data, temp_installer("absolute"));
+        assert!(matches!(result.rejection, Some(InstallRejection::AbsolutePath(_))));
+    }
+
+    #[test]
+    fn test_rejects_symlinks() {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer.start_file("manifest.json", zip::write::FileOptions::default()).unwrap();
+        writer.write_all(MANIFEST.as_bytes()).unwrap();
+        writer.add_symlink("link", "/etc/passwd", zip::write::FileOptions::default()).unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let result = install("symlink.zip", &data, temp_installer("symlink"));
+        assert_eq!(result.rejection, Some(InstallRejection::Symlink("link".to_string())));
+    }
+
+    #[test]
+    fn test_rejects_zip_bombs() {
+        let data = build_zip(&[("manifest.json", MANIFEST), ("big.bin", &"a".repeat(4096))]);
+        let result = install("bomb.zip", &data, temp_installer("bomb").with_limits(1024, 100));
+        assert!(matches!(result.rejection, Some(InstallRejection::TooLarge { .. })));
+
+        let result = install("many.zip", &data, temp_installer("many").with_limits(1024 * 1024, 1));
+        assert_eq!(result.rejection, Some(InstallRejection::TooManyFiles { count: 2, limit: 1 }));
+    }
+
+    #[test]
+    fn test_rejects_missing_manifest() {
+        let data = build_zip(&[("plugin.js", "export default {}"), ("package.json", r#"{"name": "demo"}"#)]);
+        let result = install("nomanifest.zip", &data, temp_installer("nomanifest"));
+        assert_eq!(result.rejection, Some(InstallRejection::MissingManifest));
+    }
+
+    #[test]
+    fn test_installs_package_json_plugin() {
+        let package = r#"{"name": "demo", "version": "1.0.0", "webarcade": {"id": "demo_pkg"}}"#;
+        let data = build_zip(&[("demo/package.json", package), ("demo/plugin.js", "export default {}")]);
+        let installer = temp_installer("package_json");
+        let plugins_dir = installer.plugins_dir.clone();
+
+        let result = install("demo.zip", &data, installer);
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.plugin_id, "demo_pkg");
+        assert!(plugins_dir.join("demo_pkg").join("plugin.js").exists());
+
+        let _ = fs::remove_dir_all(plugins_dir);
+    }
+
+    fn signed_installer(name: &str, signing_key: &SigningKey) -> PluginInstaller {
+        temp_installer(name).with_signature_policy(SignaturePolicy {
+            require_signed: true,
+            trusted_keys: vec![signing_key.verifying_key()],
+        })
+    }
+
+    #[test]
+    fn test_accepts_validly_signed_plugin() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let files = [("manifest.json", MANIFEST), ("demo.dll", "binary")];
+        let signature = sign_plugin_zip(&build_zip(&files), &signing_key).unwrap();
+        let data = build_zip(&[files[0], files[1], ("signature", signature.as_str())]);
+
+        let installer = signed_installer("signed", &signing_key);
+        let plugins_dir = installer.plugins_dir.clone();
+        let result = install("signed.zip", &data, installer);
+        assert!(result.success, "{}", result.message);
+
+        let _ = fs::remove_dir_all(plugins_dir);
+    }
+
+    #[test]
+    fn test_rejects_tampered_plugin() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let signature = sign_plugin_zip(&build_zip(&[("manifest.json", MANIFEST), ("demo.dll", "binary")]), &signing_key).unwrap();
+        let data = build_zip(&[("manifest.json", MANIFEST), ("demo.dll", "patched"), ("signature", signature.as_str())]);
+
+        let result = install("tampered.zip", &data, signed_installer("tampered", &signing_key));
+        assert!(matches!(result.rejection, Some(InstallRejection::InvalidSignature(_))));
+    }
+
+    #[test]
+    fn test_rejects_untrusted_signer() {
+        let signature = sign_plugin_zip(&build_zip(&[("manifest.json", MANIFEST)]), &SigningKey::from_bytes(&[9u8; 32])).unwrap();
+        let data = build_zip(&[("manifest.json", MANIFEST), ("signature", signature.as_str())]);
+
+        let result = install("untrusted.zip", &data, signed_installer("untrusted", &SigningKey::from_bytes(&[7u8; 32])));
+        assert!(matches!(result.rejection, Some(InstallRejection::InvalidSignature(_))));
+    }
+
+    #[test]
+    fn test_unsigned_plugin_depends_on_policy() {
+        let data = build_zip(&[("manifest.json", MANIFEST)]);
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+
+        let result = install("unsigned.zip", &data, signed_installer("unsigned", &signing_key));
+        assert_eq!(result.rejection, Some(InstallRejection::Unsigned));
+
+        let installer = temp_installer("unsigned_allowed");
+        let plugins_dir = installer.plugins_dir.clone();
+        assert!(install("unsigned.zip", &data, installer).success);
+        let _ = fs::remove_dir_all(plugins_dir);
+    }
+
+    #[test]
+    fn test_signed_plugin_without_trusted_keys_installs_like_unsigned() {
+        let signature = sign_plugin_zip(&build_zip(&[("manifest.json", MANIFEST)]), &SigningKey::from_bytes(&[9u8; 32])).unwrap();
+        let data = build_zip(&[("manifest.json", MANIFEST), ("signature", signature.as_str())]);
+
+        let installer = temp_installer("signed_no_keys");
+        let plugins_dir = installer.plugins_dir.clone();
+        let result = install("signed_no_keys.zip", &data, installer);
+        assert!(result.success, "{}", result.message);
+        let _ = fs::remove_dir_all(plugins_dir);
+    }
+
+    #[test]
+    fn test_signature_policy_is_read_from_config() {
+        let installer = temp_installer("policy_from_config");
+        let plugins_dir = installer.plugins_dir.clone();
+        let _ = fs::remove_dir_all(&plugins_dir);
+        fs::create_dir_all(&plugins_dir).unwrap();
+        fs::write(&installer.config_path, r#"{"name": "WebArcade", "version": "0.1.0", "plugins": {}, "requireSignedPlugins": true}"#).unwrap();
+
+        let result = install("unsigned.zip", &build_zip(&[("manifest.json", MANIFEST)]), installer);
+        assert_eq!(result.rejection, Some(InstallRejection::Unsigned));
+        let _ = fs::remove_dir_all(plugins_dir);
+    }
+
+    #[test]
+    fn test_install_and_load_registers_and_loads_plugin() {
+        let manifest = r#"{"id": "demo_live", "name": "Demo Live", "version": "1.2.0", "has_backend": false}"#;
+        let data = build_zip(&[("manifest.json", manifest), ("plugin.js", "export default {}")]);
+        let installer = temp_installer("install_and_load");
+        let plugins_dir = installer.plugins_dir.clone();
+        let config_path = installer.config_path.clone();
+        let _ = fs::remove_dir_all(&plugins_dir);
+
+        let result = installer.install_and_load(&data, "demo_live.zip").unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.loaded_plugins, vec!["demo_live".to_string()]);
+        assert!(!result.requires_restart);
+
+        let config = crate::bridge::core::dynamic_plugin_loader::WebArcadeConfig::load(&config_path).unwrap();
+        let entry = &config.plugins["demo_live"];
+        assert!(entry.enabled);
+        assert!(!entry.has_backend);
+        assert_eq!(entry.path, "demo_live/plugin.js");
+        assert_eq!(entry.version, "1.2.0");
+
+        let _ = fs::remove_dir_all(plugins_dir);
+    }
 }